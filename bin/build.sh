@@ -20,6 +20,11 @@ function replace() {
 # fix types
 echo "  🩹  Fixing types..."
 for t in lib_*/*.d.ts; do
+  replace '/^export class RLexer /,/^}/s/constructor(rules: any, options: any);/constructor(rules: LexerRule[] | Record<string, LexerRule[]>, options?: LexerOptions);/g' $t
+  replace '/^export class RLiteralSet /,/^}/s/constructor(patterns: string\[\], options: any);/constructor(patterns: string[], options?: LiteralSetOptions);/g' $t
+  replace '/^export class RLiteralSet /,/^}/s/find(text: string): any;/find(text: string): LiteralMatch | undefined;/g' $t
+  replace '/^export class RLiteralSet /,/^}/s/findAll(text: string): any;/findAll(text: string): LiteralMatch[];/g' $t
+  replace '/^export class RLiteralSet /,/^}/s/findOverlapping(text: string): any;/findOverlapping(text: string): LiteralMatch[];/g' $t
   replace 's/find(text: string): any;/find(text: string): Match | undefined;/g' $t
   replace 's/findAt(text: string, start: number): any;/findAt(text: string, start: number): Match | undefined;/g' $t
   replace 's/findAll(text: string): any;/findAll(text: string): Match[];/g' $t
@@ -31,6 +36,52 @@ for t in lib_*/*.d.ts; do
   replace 's/captures(text: string): any;/captures(text: string): Captures | undefined;/g' $t
   replace 's/capturesAll(text: string): any;/capturesAll(text: string): Captures[];/g' $t
   replace 's/matches(text: string): any\[\];/matches(text: string): number[];/g' $t
+  replace 's/literals(options: any): any\[\];/literals(options?: LiteralsOptions): LiteralSeq[];/g' $t
+  replace 's/literals(options: any): any;/literals(options?: LiteralsOptions): LiteralSeq;/g' $t
+  replace 's/static toJsRegExp(pattern: string): any;/static toJsRegExp(pattern: string): JsRegExp;/g' $t
+  replace 's/constructor(re: string, options: any);/constructor(re: string, options?: RegexOptions);/g' $t
+  replace '/^export class RRegexSet /,/^}/s/constructor(list: Array<any>, options: any);/constructor(list: string[], options?: RegexOptions);/g' $t
+  replace 's/static fromJsRegExp(source: any, flags?: \(string[^)]*\)): RRegex;/static fromJsRegExp(source: string | RegExp, flags?: \1): RRegex;/g' $t
+  replace 's/constructor(glob: string, options: any);/constructor(glob: string, options?: GlobOptions);/g' $t
+  replace '/^export class RGlobSet /,/^}/s/constructor(list: Array<any>, options: any);/constructor(list: string[], options?: GlobOptions);/g' $t
+  replace 's/matches(path: string): any\[\];/matches(path: string): number[];/g' $t
+  replace 's/syntax(): any\[\];/syntax(): Hir[];/g' $t
+  replace 's/constructor(rules: any, options: any);/constructor(rules: string | string[], options?: IgnoreOptions);/g' $t
+  replace 's/matched(path: string, is_dir: boolean): any;/matched(path: string, isDir: boolean): IgnoreMatch | undefined;/g' $t
+  replace 's/matchedPathOrAnyParents(path: string, is_dir: boolean): any;/matchedPathOrAnyParents(path: string, isDir: boolean): IgnoreMatch | undefined;/g' $t
+  replace 's/isIgnored(path: string, is_dir: boolean): boolean;/isIgnored(path: string, isDir: boolean): boolean;/g' $t
+  replace 's/static fromPosix(pattern: string, syntax: string, options: any): RRegex;/static fromPosix(pattern: string, syntax: "bre" | "ere", options?: RegexOptions): RRegex;/g' $t
+  replace 's/static fromSqlLike(pattern: string, escape[^,]*, options: any): RRegex;/static fromSqlLike(pattern: string, escape?: string | null, options?: RegexOptions): RRegex;/g' $t
+  replace 's/static fromSqlSimilarTo(pattern: string, escape[^,]*, options: any): RRegex;/static fromSqlSimilarTo(pattern: string, escape?: string | null, options?: RegexOptions): RRegex;/g' $t
+  replace 's/tree(): any;/tree(): ExplainNode;/g' $t
+  replace 's/generate(options: any): string\[\];/generate(options?: GenerateOptions): string[];/g' $t
+  replace 's/enumerate(options: any): string\[\];/enumerate(options?: EnumerateOptions): string[];/g' $t
+  replace 's/isSubsetOf(other: RRegex, options: any): any;/isSubsetOf(other: RRegex, options?: LanguageOptions): LanguageCheck;/g' $t
+  replace 's/isEquivalent(other: RRegex, options: any): any;/isEquivalent(other: RRegex, options?: LanguageOptions): LanguageCheck;/g' $t
+  replace 's/intersects(other: RRegex, options: any): any;/intersects(other: RRegex, options?: LanguageOptions): LanguageCheck;/g' $t
+  replace 's/analyze(): any;/analyze(): PatternAnalysis[];/g' $t
+  replace 's/toDot(options: any): string;/toDot(options?: DotOptions): string;/g' $t
+  replace 's/info(): any;/info(): RegexInfo;/g' $t
+  replace '/^export class RRegexCache /,/^}/s/static stats(): any;/static stats(): CacheStats;/g' $t
+  replace 's/stats(): any;/stats(): RegexStats;/g' $t
+  replace 's/static cached(\([^,]*\), options: any)/static cached(\1, options?: RegexOptions)/g' $t
+  replace 's/static evict(pattern: any, options: any): boolean;/static evict(pattern: string | string[], options?: RegexOptions): boolean;/g' $t
+  replace 's/toDescriptor(options: any): any;/toDescriptor(options?: DescriptorOptions): RegexDescriptor;/g' $t
+  replace 's/toDescriptor(): any;/toDescriptor(): RegexSetDescriptor;/g' $t
+  replace 's/static fromDescriptor(descriptor: any): RRegex\(Set\)\{0,1\};/static fromDescriptor(descriptor: Regex\1Descriptor): RRegex\1;/g' $t
+  replace 's/static fromTemplate(strings: any, ...values: any\[\]): RRegex;/static fromTemplate(strings: TemplateStringsArray | string[], ...values: TemplateValue[]): RRegex;/g' $t
+  replace 's/export function unescape(pattern: string, options: any): string;/export function unescape(pattern: string, options?: RegexOptions): string;/g' $t
+  replace 's/static fromLiterals(words: string\[\], options: any): RRegex;/static fromLiterals(words: string[], options?: LiteralListOptions): RRegex;/g' $t
+  replace '/^export class RGrok /,/^}/s/constructor(definitions: any);/constructor(definitions?: Record<string, string>);/g' $t
+  replace '/^export class RGrok /,/^}/s/compile(pattern: string, options: any): RRegex;/compile(pattern: string, options?: RegexOptions): RRegex;/g' $t
+  replace '/^export class RPresets /,/^}/s/static get(name: string, options: any): RRegex;/static get(name: PresetName, options?: PresetOptions): RRegex;/g' $t
+  replace '/^export class RPresets /,/^}/s/static pattern(name: string, options: any): string;/static pattern(name: PresetName, options?: PresetOptions): string;/g' $t
+  replace '/^export class RPresets /,/^}/s/static names(): string\[\];/static names(): PresetName[];/g' $t
+  replace '/^export class RSecretScanner /,/^}/s/constructor(options: any);/constructor(options?: SecretScannerOptions);/g' $t
+  replace '/^export class RSecretScanner /,/^}/s/static builtinRules(): any;/static builtinRules(): SecretRule[];/g' $t
+  replace '/^export class RSecretScanner /,/^}/s/scan(text: string): any;/scan(text: string): SecretFinding[];/g' $t
+  replace '/^export class RLexer /,/^}/s/tokenize(text: string): any;/tokenize(text: string): Token[];/g' $t
+  replace '/^export class RScanner /,/^}/s/captures(): any;/captures(): Captures | undefined;/g' $t
 done
 
 echo "  📦  Bundling Cloudflare bundle..."
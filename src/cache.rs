@@ -0,0 +1,289 @@
+use regex::{Regex, RegexSet};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+use crate::options::{RegexEngine, RegexOptions};
+use crate::types;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+#[wasm_bindgen(typescript_custom_section)]
+const CACHE_STATS_TYPE: &'static str = r#"/**
+ * The state of the cache shared by `RRegex.cached` and `RRegexSet.cached`,
+ * see `RRegexCache.stats`.
+ */
+export type CacheStats = {
+  /** The maximum number of compiled patterns kept. */
+  capacity: number
+
+  /** The number of compiled patterns currently kept. */
+  size: number
+
+  /** The number of lookups that reused a compiled pattern. */
+  hits: number
+
+  /** The number of lookups that had to compile the pattern. */
+  misses: number
+
+  /**
+   * The number of compiled patterns dropped to make room for another one,
+   * or because the capacity was lowered. Explicit evictions and clears are
+   * not counted.
+   */
+  evictions: number
+}"#;
+
+/// The number of compiled patterns kept until `setCapacity` is called.
+const DEFAULT_CAPACITY: usize = 128;
+
+/// A pattern, or the patterns of a set, with the options they are compiled
+/// with.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Regex(String, RegexOptions),
+    Set(Vec<String>, RegexOptions),
+}
+
+impl Key {
+    /// The options that don't change the compiled pattern (the engine,
+    /// which is built apart, and profiling) are left out of the key, so
+    /// every combination of them shares the same entry.
+    fn new(pattern: PatternKey, options: &RegexOptions) -> Key {
+        let options = RegexOptions {
+            engine: RegexEngine::Auto,
+            profile: false,
+            ..options.clone()
+        };
+        match pattern {
+            PatternKey::Regex(pattern) => Key::Regex(pattern, options),
+            PatternKey::Set(patterns) => Key::Set(patterns, options),
+        }
+    }
+}
+
+enum PatternKey {
+    Regex(String),
+    Set(Vec<String>),
+}
+
+/// Compiled patterns are reference counted, so cloning one is cheap and
+/// shares its compiled program.
+#[derive(Clone)]
+enum Compiled {
+    Regex(Regex),
+    Set(RegexSet),
+}
+
+#[derive(Serialize)]
+struct CacheStats {
+    capacity: usize,
+    size: usize,
+    hits: usize,
+    misses: usize,
+    evictions: usize,
+}
+
+/// A least recently used cache, where every entry remembers when it was
+/// last used, and the oldest one is looked up when the cache is full.
+struct Lru {
+    capacity: usize,
+    entries: HashMap<Key, (Compiled, u64)>,
+    clock: u64,
+    hits: usize,
+    misses: usize,
+    evictions: usize,
+}
+
+impl Lru {
+    fn new() -> Lru {
+        Lru {
+            capacity: DEFAULT_CAPACITY,
+            entries: HashMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    /// Returns the entry of `key`, compiling and inserting it when missing.
+    fn get<F>(&mut self, key: Key, compile: F) -> Result<Compiled>
+    where
+        F: FnOnce() -> Result<Compiled>,
+    {
+        self.clock += 1;
+        if let Some((compiled, used)) = self.entries.get_mut(&key) {
+            *used = self.clock;
+            self.hits += 1;
+            return Ok(compiled.clone());
+        }
+
+        self.misses += 1;
+        let compiled = compile()?;
+        if self.capacity > 0 {
+            self.shrink(self.capacity - 1);
+            self.entries.insert(key, (compiled.clone(), self.clock));
+        }
+        Ok(compiled)
+    }
+
+    /// Drops the least recently used entries until at most `len` are left.
+    fn shrink(&mut self, len: usize) {
+        while self.entries.len() > len {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+                self.evictions += 1;
+            }
+        }
+    }
+}
+
+thread_local! {
+    static CACHE: RefCell<Lru> = RefCell::new(Lru::new());
+}
+
+/// Returns the compiled `pattern`, from the cache when possible.
+pub fn regex(pattern: &str, options: &RegexOptions) -> Result<Regex> {
+    let key = Key::new(PatternKey::Regex(pattern.to_owned()), options);
+    let compiled = CACHE.with(|cache| {
+        cache.borrow_mut().get(key, || {
+            let regex = options
+                .regex_builder(pattern)
+                .build()
+                .map_err(serde_wasm_bindgen::Error::new)?;
+            Ok(Compiled::Regex(regex))
+        })
+    })?;
+    match compiled {
+        Compiled::Regex(regex) => Ok(regex),
+        Compiled::Set(_) => unreachable!("the key of a regex can't hold a set"),
+    }
+}
+
+/// Returns the compiled set of `patterns`, from the cache when possible.
+pub fn regex_set(patterns: &[String], options: &RegexOptions) -> Result<RegexSet> {
+    let key = Key::new(PatternKey::Set(patterns.to_vec()), options);
+    let compiled = CACHE.with(|cache| {
+        cache.borrow_mut().get(key, || {
+            let set = options
+                .regex_set_builder(patterns)
+                .build()
+                .map_err(|err| serde_wasm_bindgen::Error::new(err.to_string()))?;
+            Ok(Compiled::Set(set))
+        })
+    })?;
+    match compiled {
+        Compiled::Set(set) => Ok(set),
+        Compiled::Regex(_) => unreachable!("the key of a set can't hold a regex"),
+    }
+}
+
+/// The cache of compiled patterns shared by `RRegex.cached` and
+/// `RRegexSet.cached`, which avoids compiling the same pattern again, e.g.
+/// when patterns come from a configuration read on every request.
+///
+/// The least recently used patterns are dropped when the cache is full. A
+/// pattern is cached with the options it was compiled with, except the
+/// `engine` and `profile` options, which are applied to every instance.
+///
+/// # Example
+///
+/// ```typescript
+/// import { RRegex, RRegexCache } from "rregex"
+///
+/// RRegexCache.setCapacity(2)
+/// RRegex.cached("a+")
+/// RRegex.cached("a+")
+/// RRegex.cached("b+")
+/// RRegex.cached("c+")
+/// expect(RRegexCache.stats()).toEqual({
+///   capacity: 2,
+///   size: 2,
+///   hits: 1,
+///   misses: 3,
+///   evictions: 1,
+/// })
+/// ```
+#[wasm_bindgen]
+pub struct RRegexCache {}
+
+#[wasm_bindgen]
+impl RRegexCache {
+    /// Changes the maximum number of compiled patterns kept, dropping the
+    /// least recently used ones when there are more. A capacity of `0`
+    /// disables the cache. Defaults to `128`.
+    ///
+    /// @param {number} capacity - The maximum number of compiled patterns
+    #[wasm_bindgen(skip_jsdoc, js_name = setCapacity)]
+    pub fn set_capacity(capacity: usize) {
+        CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            cache.capacity = capacity;
+            cache.shrink(capacity);
+        })
+    }
+
+    /// Returns the capacity, the size and the counters of the cache.
+    ///
+    /// @returns {CacheStats}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn stats() -> Result<JsValue> {
+        CACHE.with(|cache| {
+            let cache = cache.borrow();
+            serde_wasm_bindgen::to_value(&CacheStats {
+                capacity: cache.capacity,
+                size: cache.entries.len(),
+                hits: cache.hits,
+                misses: cache.misses,
+                evictions: cache.evictions,
+            })
+        })
+    }
+
+    /// Resets the hits, misses and evictions counted so far.
+    #[wasm_bindgen(js_name = resetStats)]
+    pub fn reset_stats() {
+        CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            cache.hits = 0;
+            cache.misses = 0;
+            cache.evictions = 0;
+        })
+    }
+
+    /// Drops a pattern (or, given an array, a set of patterns) compiled with
+    /// the given options. Returns whether it was cached.
+    ///
+    /// Instances already returned by `cached` keep working, they share the
+    /// compiled pattern until they are freed.
+    ///
+    /// @param {string | string[]} pattern - The pattern, or the patterns of a set
+    /// @param {RegexOptions} options - The options the pattern was compiled with
+    /// @returns {boolean}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn evict(pattern: JsValue, options: JsValue) -> Result<bool> {
+        let pattern = if pattern.is_array() {
+            PatternKey::Set(serde_wasm_bindgen::from_value(pattern)?)
+        } else {
+            let pattern = pattern.as_string().ok_or_else(|| {
+                serde_wasm_bindgen::Error::new("pattern is not a string or an array")
+            })?;
+            PatternKey::Regex(pattern)
+        };
+        let options: RegexOptions = types::options(options)?;
+        let key = Key::new(pattern, &options);
+        Ok(CACHE.with(|cache| cache.borrow_mut().entries.remove(&key).is_some()))
+    }
+
+    /// Drops every compiled pattern, without resetting the counters.
+    pub fn clear() {
+        CACHE.with(|cache| cache.borrow_mut().entries.clear())
+    }
+}
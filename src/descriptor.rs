@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::options::RegexOptions;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+#[wasm_bindgen(typescript_custom_section)]
+const DESCRIPTOR_TYPE: &'static str = r#"/**
+ * A plain object describing a `RRegex`, which can be sent to a worker with
+ * `postMessage` (or stored as JSON, without the automaton) and compiled
+ * again with `RRegex.fromDescriptor`.
+ */
+export type RegexDescriptor = {
+  /** The regular expression. */
+  pattern: string
+
+  /** The options the regular expression was compiled with. */
+  options: RegexOptions
+
+  /**
+   * The forward and reverse DFAs of the `dfa` engine, serialized in the
+   * little endian format of `regex-automata`, to load them instead of
+   * building them again. See `RRegex.toDescriptor`.
+   */
+  automaton?: {
+    forward: Uint8Array
+    reverse: Uint8Array
+  }
+}
+
+/**
+ * A plain object describing a `RRegexSet`, see `RRegexSet.fromDescriptor`.
+ */
+export type RegexSetDescriptor = {
+  /** The regular expressions in the set. */
+  patterns: string[]
+
+  /** The options the regular expressions were compiled with. */
+  options: RegexOptions
+}
+
+/**
+ * Options accepted by `RRegex.toDescriptor`.
+ */
+export type DescriptorOptions = {
+  /**
+   * Includes the DFAs of a regular expression compiled with
+   * `engine: "dfa"`, which are the most expensive engine to build.
+   * Defaults to `false`.
+   */
+  automaton?: boolean
+}"#;
+
+#[derive(Serialize, Deserialize)]
+pub struct RegexDescriptor {
+    pub pattern: String,
+    #[serde(default)]
+    pub options: RegexOptions,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RegexSetDescriptor {
+    pub patterns: Vec<String>,
+    #[serde(default)]
+    pub options: RegexOptions,
+}
+
+#[derive(Deserialize, Default)]
+pub struct DescriptorOptions {
+    #[serde(default)]
+    pub automaton: bool,
+}
+
+/// Serializes `descriptor`, adding the forward and reverse DFAs of
+/// `automaton` as typed arrays, which are copied by `postMessage` at once
+/// instead of element by element.
+pub fn to_value<T: Serialize>(
+    descriptor: &T,
+    automaton: Option<(Vec<u8>, Vec<u8>)>,
+) -> Result<JsValue> {
+    let value = serde_wasm_bindgen::to_value(descriptor)?;
+    if let Some((forward, reverse)) = automaton {
+        let bytes = js_sys::Object::new();
+        let forward = js_sys::Uint8Array::from(&forward[..]);
+        let reverse = js_sys::Uint8Array::from(&reverse[..]);
+        js_sys::Reflect::set(&bytes, &JsValue::from("forward"), &forward)?;
+        js_sys::Reflect::set(&bytes, &JsValue::from("reverse"), &reverse)?;
+        js_sys::Reflect::set(&value, &JsValue::from("automaton"), &bytes)?;
+    }
+    Ok(value)
+}
+
+/// Returns the forward and reverse DFAs of a serialized descriptor, if it
+/// has them.
+pub fn automaton(descriptor: &JsValue) -> Result<Option<(Vec<u8>, Vec<u8>)>> {
+    let automaton = js_sys::Reflect::get(descriptor, &JsValue::from("automaton"))?;
+    if automaton.is_undefined() || automaton.is_null() {
+        return Ok(None);
+    }
+    let bytes = |name: &str| -> Result<Vec<u8>> {
+        let value = js_sys::Reflect::get(&automaton, &JsValue::from(name))?;
+        if !value.is_instance_of::<js_sys::Uint8Array>() {
+            return Err(serde_wasm_bindgen::Error::new(format!(
+                "automaton.{} is not an Uint8Array",
+                name
+            )));
+        }
+        Ok(js_sys::Uint8Array::from(value).to_vec())
+    };
+    Ok(Some((bytes("forward")?, bytes("reverse")?)))
+}
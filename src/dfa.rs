@@ -0,0 +1,410 @@
+use regex_automata::dfa::{dense, Automaton, StartKind};
+use regex_automata::nfa::thompson::{self, Transition};
+use regex_automata::util::look;
+use regex_automata::util::primitives::StateID;
+use regex_automata::{Anchored, Input, MatchKind};
+use regex_syntax::hir::{Dot, Hir, Look, Repetition};
+use std::collections::{HashMap, HashSet};
+
+pub type Dfa = dense::DFA<Vec<u32>>;
+
+/// The size limit, in bytes, of the DFAs built when the options don't set
+/// `dfaSizeLimit`, since some patterns, like `(?s).*a.{24}`, have an
+/// exponential number of states.
+const DEFAULT_SIZE_LIMIT: usize = 10 * (1 << 20);
+
+/// Builds a DFA that only accepts the strings that `hir` matches as a whole,
+/// so the states of the DFA describe the language of the pattern.
+///
+/// Every match is reported (instead of only the leftmost-first one), so a
+/// state is accepting at the end of the input exactly when the consumed
+/// string is in the language.
+///
+/// Throws when the DFA would be larger than `size_limit` bytes, which
+/// defaults to 10 MB.
+pub fn whole_match(hir: &Hir, size_limit: Option<usize>) -> Result<Dfa, serde_wasm_bindgen::Error> {
+    let hir = Hir::concat(vec![hir.clone(), Hir::look(Look::End)]);
+    let nfa = thompson::Compiler::new()
+        .build_from_hir(&hir)
+        .map_err(serde_wasm_bindgen::Error::new)?;
+    determinize(&nfa, size_limit)
+}
+
+fn determinize(
+    nfa: &thompson::NFA,
+    size_limit: Option<usize>,
+) -> Result<Dfa, serde_wasm_bindgen::Error> {
+    let size_limit = size_limit.unwrap_or(DEFAULT_SIZE_LIMIT);
+    dense::Builder::new()
+        .configure(
+            dense::Config::new()
+                .match_kind(MatchKind::All)
+                .start_kind(StartKind::Anchored)
+                .dfa_size_limit(Some(size_limit))
+                .determinize_size_limit(Some(size_limit)),
+        )
+        .build_from_nfa(nfa)
+        .map_err(serde_wasm_bindgen::Error::new)
+}
+
+/// Builds a DFA that accepts the strings in which `hir` matches somewhere,
+/// like an unanchored search does.
+pub fn search_match(
+    hir: &Hir,
+    size_limit: Option<usize>,
+) -> Result<Dfa, serde_wasm_bindgen::Error> {
+    let hir = Hir::concat(vec![any_string(), hir.clone(), any_string()]);
+    whole_match(&hir, size_limit)
+}
+
+/// Builds a DFA that accepts every string.
+pub fn all_strings() -> Result<Dfa, serde_wasm_bindgen::Error> {
+    whole_match(&any_string(), None)
+}
+
+/// `(?s:.)*`
+fn any_string() -> Hir {
+    Hir::repetition(Repetition {
+        min: 0,
+        max: None,
+        greedy: true,
+        sub: Box::new(Hir::dot(Dot::AnyChar)),
+    })
+}
+
+/// Serializes `dfa` in the little endian format of `regex-automata`,
+/// without the padding used to align it in memory.
+pub fn to_bytes(dfa: &Dfa) -> Vec<u8> {
+    let (bytes, padding) = dfa.to_bytes_little_endian();
+    bytes[padding..].to_vec()
+}
+
+/// Loads a DFA serialized with `to_bytes`.
+pub fn from_bytes(bytes: &[u8]) -> Result<Dfa, serde_wasm_bindgen::Error> {
+    // the DFA is read in place, which requires an alignment of 4 bytes
+    let mut aligned = vec![0; bytes.len() + 3];
+    let offset = aligned.as_ptr().align_offset(4);
+    aligned[offset..offset + bytes.len()].copy_from_slice(bytes);
+    let (dfa, _) = dense::DFA::from_bytes(&aligned[offset..offset + bytes.len()])
+        .map_err(serde_wasm_bindgen::Error::new)?;
+    Ok(dfa.to_owned())
+}
+
+/// Returns the state of `dfa` before reading any input.
+pub fn start(dfa: &Dfa) -> StateID {
+    dfa.start_state_forward(&Input::new("").anchored(Anchored::Yes))
+        .expect("an anchored search of a DFA without quit bytes can't fail")
+}
+
+/// Whether the string read to reach `state` is accepted.
+pub fn is_accepting(dfa: &Dfa, state: StateID) -> bool {
+    dfa.is_match_state(dfa.next_eoi_state(state))
+}
+
+/// Whether `dfa` accepts `bytes` as a whole.
+pub fn accepts(dfa: &Dfa, bytes: &[u8]) -> bool {
+    let mut state = start(dfa);
+    for &byte in bytes {
+        state = dfa.next_state(state, byte);
+        if dfa.is_dead_state(state) || dfa.is_quit_state(state) {
+            return false;
+        }
+    }
+    is_accepting(dfa, state)
+}
+
+/// Builds a DFA accepting the strings for which `accept` returns `true`,
+/// given whether `a` and `b` accept them, like the intersection (`a && b`)
+/// or the difference (`a && !b`) of their languages.
+///
+/// The reachable pairs of states of the product of `a` and `b` are written
+/// as an NFA with a single transition per byte, which is then determinized
+/// but not minimized, since minimizing costs far more than building it.
+/// `keep` is called with whether each side is still live and prunes the
+/// pairs that can't lead to an accepted string.
+pub fn combine<K, F>(a: &Dfa, b: &Dfa, keep: K, accept: F) -> Result<Dfa, serde_wasm_bindgen::Error>
+where
+    K: Fn(bool, bool) -> bool,
+    F: Fn(bool, bool) -> bool,
+{
+    let error = serde_wasm_bindgen::Error::new;
+    let mut builder = thompson::Builder::new();
+    builder.start_pattern().map_err(error)?;
+    let matched = builder.add_match().map_err(error)?;
+    let end = builder.add_look(matched, look::Look::End).map_err(error)?;
+
+    // every pair is a union state, patched with its transitions once the
+    // pair is visited, since the states it leads to may not exist yet
+    let start_pair = (start(a), start(b));
+    let start_id = builder.add_union(vec![]).map_err(error)?;
+    let mut ids = HashMap::new();
+    ids.insert(start_pair, start_id);
+    let mut pending = vec![start_pair];
+    while let Some((a_state, b_state)) = pending.pop() {
+        let id = ids[&(a_state, b_state)];
+        if accept(is_accepting(a, a_state), is_accepting(b, b_state)) {
+            builder.patch(id, end).map_err(error)?;
+        }
+        let mut transitions: Vec<Transition> = Vec::new();
+        for byte in 0..=255 {
+            let pair = (a.next_state(a_state, byte), b.next_state(b_state, byte));
+            if !keep(!a.is_dead_state(pair.0), !b.is_dead_state(pair.1)) {
+                continue;
+            }
+            let next = match ids.get(&pair) {
+                Some(next) => *next,
+                None => {
+                    let next = builder.add_union(vec![]).map_err(error)?;
+                    ids.insert(pair, next);
+                    pending.push(pair);
+                    next
+                }
+            };
+            match transitions.last_mut() {
+                Some(last) if last.next == next && last.end + 1 == byte => last.end = byte,
+                _ => transitions.push(Transition {
+                    start: byte,
+                    end: byte,
+                    next,
+                }),
+            }
+        }
+        let sparse = builder.add_sparse(transitions).map_err(error)?;
+        builder.patch(id, sparse).map_err(error)?;
+    }
+    builder.finish_pattern(start_id).map_err(error)?;
+    let nfa = builder.build(start_id, start_id).map_err(error)?;
+    determinize(&nfa, None)
+}
+
+/// Calls `f` with the UTF-8 encoding of every character that leads from
+/// `state` to a state for which `keep` returns `true`, and that state, in
+/// ascending order of the characters. Stops as soon as `f` returns `false`,
+/// and returns `false` in that case.
+///
+/// Bytes of the same class have the same transitions, so whether a partial
+/// encoding can still lead to a kept state is only checked once per class,
+/// and the encodings that can't are never completed.
+pub fn each_char<K, F>(dfa: &Dfa, state: StateID, keep: K, f: &mut F) -> bool
+where
+    K: Fn(StateID) -> bool,
+    F: FnMut(&[u8], StateID) -> bool,
+{
+    let mut chars = EachChar {
+        dfa,
+        keep,
+        viable: HashMap::new(),
+        buf: [0; 4],
+    };
+    chars.step(state, 0, 0, f)
+}
+
+struct EachChar<'d, K> {
+    dfa: &'d Dfa,
+    keep: K,
+    /// Whether a state can lead to a kept state with a number of
+    /// continuation bytes.
+    viable: HashMap<(StateID, usize), bool>,
+    buf: [u8; 4],
+}
+
+impl<'d, K> EachChar<'d, K>
+where
+    K: Fn(StateID) -> bool,
+{
+    fn step<F>(&mut self, state: StateID, len: usize, need: usize, f: &mut F) -> bool
+    where
+        F: FnMut(&[u8], StateID) -> bool,
+    {
+        if len > 0 && len == need {
+            return f(&self.buf[..len], state);
+        }
+        let bytes = if len == 0 { 0x00..=0xF4 } else { 0x80..=0xBF };
+        for byte in bytes {
+            let need = if len == 0 {
+                match utf8_len(byte) {
+                    Some(need) => need,
+                    None => continue,
+                }
+            } else {
+                need
+            };
+            let next = self.dfa.next_state(state, byte);
+            if !self.is_viable(next, need - len - 1) {
+                continue;
+            }
+            self.buf[len] = byte;
+            if !self.step(next, len + 1, need, f) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Whether `state` leads to a kept state after `left` continuation
+    /// bytes.
+    fn is_viable(&mut self, state: StateID, left: usize) -> bool {
+        if self.dfa.is_dead_state(state) {
+            return false;
+        }
+        if left == 0 {
+            return (self.keep)(state);
+        }
+        if let Some(viable) = self.viable.get(&(state, left)) {
+            return *viable;
+        }
+        let classes = self.dfa.byte_classes();
+        let mut seen = HashSet::new();
+        let viable = (0x80..=0xBF)
+            .filter(|byte| seen.insert(classes.get(*byte)))
+            .any(|byte| self.is_viable(self.dfa.next_state(state, byte), left - 1));
+        self.viable.insert((state, left), viable);
+        viable
+    }
+}
+
+/// Returns the states reachable from `state` by reading one character.
+pub fn successors(dfa: &Dfa, state: StateID) -> Vec<StateID> {
+    let classes = dfa.byte_classes();
+    let mut found = Vec::new();
+    let mut seen = HashSet::new();
+    let mut pending = Vec::new();
+    for byte in 0x00..=0xF4 {
+        if let Some(need) = utf8_len(byte) {
+            // bytes of the same class have the same transitions
+            if seen.insert((classes.get(byte), need)) {
+                pending.push((dfa.next_state(state, byte), need - 1));
+            }
+        }
+    }
+
+    let mut visited = HashSet::new();
+    while let Some((state, need)) = pending.pop() {
+        if dfa.is_dead_state(state) || !visited.insert((state, need)) {
+            continue;
+        }
+        if need == 0 {
+            found.push(state);
+            continue;
+        }
+        let mut classes_seen = HashSet::new();
+        for byte in 0x80..=0xBF {
+            if classes_seen.insert(classes.get(byte)) {
+                pending.push((dfa.next_state(state, byte), need - 1));
+            }
+        }
+    }
+    found
+}
+
+/// Returns the length of the UTF-8 encoding that starts with `byte`, or
+/// `None` if `byte` can't start one.
+fn utf8_len(byte: u8) -> Option<usize> {
+    match byte {
+        0x00..=0x7F => Some(1),
+        0xC2..=0xDF => Some(2),
+        0xE0..=0xEF => Some(3),
+        0xF0..=0xF4 => Some(4),
+        _ => None,
+    }
+}
+
+/// The product of two DFAs, reading characters in both at the same time.
+pub struct Product<'d> {
+    a: &'d Dfa,
+    b: &'d Dfa,
+    /// The smallest lead byte of each set of bytes that have the same
+    /// transitions in both DFAs, with the length of its encoding.
+    leads: Vec<(u8, usize)>,
+    /// Same as `leads`, for continuation bytes.
+    continuations: Vec<u8>,
+}
+
+impl<'d> Product<'d> {
+    pub fn new(a: &'d Dfa, b: &'d Dfa) -> Self {
+        let classes = |byte: u8| (a.byte_classes().get(byte), b.byte_classes().get(byte));
+        let mut seen = HashSet::new();
+        let leads = (0x00..=0xF4)
+            .filter_map(|byte| utf8_len(byte).map(|need| (byte, need)))
+            .filter(|(byte, need)| seen.insert((classes(*byte), *need)))
+            .collect();
+        let mut seen = HashSet::new();
+        let continuations = (0x80..=0xBF)
+            .filter(|byte| seen.insert(classes(*byte)))
+            .collect();
+        Product {
+            a,
+            b,
+            leads,
+            continuations,
+        }
+    }
+
+    /// Returns, for every distinct pair of states reachable from `(a_state,
+    /// b_state)` by reading one character, the smallest character that
+    /// reaches it, in ascending order of the characters.
+    ///
+    /// `keep` is called with whether each side is still live (not dead),
+    /// and decides whether the pair is worth exploring.
+    pub fn successors<K>(
+        &self,
+        a_state: StateID,
+        b_state: StateID,
+        keep: K,
+    ) -> Vec<(Vec<u8>, StateID, StateID)>
+    where
+        K: Fn(bool, bool) -> bool,
+    {
+        let mut found = Vec::new();
+        let mut visited = HashSet::new();
+        let mut buf = [0; 4];
+        for &(lead, need) in &self.leads {
+            buf[0] = lead;
+            self.step(
+                a_state,
+                b_state,
+                &mut buf,
+                1,
+                need,
+                &keep,
+                &mut visited,
+                &mut found,
+            );
+        }
+        found
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn step<K>(
+        &self,
+        a_state: StateID,
+        b_state: StateID,
+        buf: &mut [u8; 4],
+        len: usize,
+        need: usize,
+        keep: &K,
+        visited: &mut HashSet<(StateID, StateID, usize)>,
+        found: &mut Vec<(Vec<u8>, StateID, StateID)>,
+    ) where
+        K: Fn(bool, bool) -> bool,
+    {
+        let a_next = self.a.next_state(a_state, buf[len - 1]);
+        let b_next = self.b.next_state(b_state, buf[len - 1]);
+        if !keep(!self.a.is_dead_state(a_next), !self.b.is_dead_state(b_next)) {
+            return;
+        }
+        // a smaller prefix already reached the same states, so it also
+        // reaches whatever this one does
+        if !visited.insert((a_next, b_next, need - len)) {
+            return;
+        }
+        if len == need {
+            found.push((buf[..len].to_vec(), a_next, b_next));
+            return;
+        }
+        for &byte in &self.continuations {
+            buf[len] = byte;
+            self.step(a_next, b_next, buf, len + 1, need, keep, visited, found);
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use regex_automata::dfa::{dense, Automaton};
+use regex_automata::nfa::thompson::{self, State, NFA};
+use regex_automata::util::look::Look;
+use regex_automata::util::primitives::StateID;
+use regex_automata::{Input, MatchKind};
+use regex_syntax::hir::Hir;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+
+use crate::options::RegexOptions;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+#[wasm_bindgen(typescript_custom_section)]
+const DOT_OPTIONS_TYPE: &'static str = r#"/**
+ * Options accepted by `RRegex.toDot` and `RRegexSet.toDot`.
+ */
+export type DotOptions = {
+  /**
+   * The automaton to render: the Thompson NFA the pattern is compiled into
+   * (`nfa`), the dense DFA built from it (`dfa`) or the same DFA after
+   * minimization (`minimal-dfa`). Defaults to `nfa`.
+   */
+  automaton?: 'nfa' | 'dfa' | 'minimal-dfa'
+}"#;
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DotAutomaton {
+    #[default]
+    Nfa,
+    Dfa,
+    MinimalDfa,
+}
+
+#[derive(Deserialize, Default)]
+pub struct DotOptions {
+    #[serde(default)]
+    automaton: DotAutomaton,
+}
+
+/// Renders the automaton of `hirs`, one per pattern, as a Graphviz digraph.
+///
+/// A set of patterns reports every pattern that matches, like `RegexSet`,
+/// while a single pattern uses leftmost-first semantics, like `Regex`.
+pub fn to_dot(hirs: &[Hir], options: &DotOptions, regex_options: &RegexOptions) -> Result<String> {
+    let nfa = thompson::Compiler::new()
+        .configure(thompson::Config::new().nfa_size_limit(regex_options.size_limit))
+        .build_many_from_hir(hirs)
+        .map_err(serde_wasm_bindgen::Error::new)?;
+    if options.automaton == DotAutomaton::Nfa {
+        return Ok(nfa_to_dot(&nfa));
+    }
+
+    let match_kind = if hirs.len() == 1 {
+        MatchKind::LeftmostFirst
+    } else {
+        MatchKind::All
+    };
+    let dfa = dense::Builder::new()
+        .configure(
+            dense::Config::new()
+                .match_kind(match_kind)
+                .minimize(options.automaton == DotAutomaton::MinimalDfa)
+                .dfa_size_limit(regex_options.dfa_size_limit)
+                .determinize_size_limit(regex_options.dfa_size_limit),
+        )
+        .build_from_nfa(&nfa)
+        .map_err(serde_wasm_bindgen::Error::new)?;
+    dfa_to_dot(&dfa)
+}
+
+/// Renders every state of `nfa`, with the unanchored start state (which
+/// leads to the anchored one) as the entry point.
+fn nfa_to_dot(nfa: &NFA) -> String {
+    let mut dot = header();
+    let _ = writeln!(dot, "  start -> {};", nfa.start_unanchored().as_usize());
+    for (id, state) in nfa.states().iter().enumerate() {
+        match state {
+            State::ByteRange { trans } => {
+                node(&mut dot, id, None);
+                edge(
+                    &mut dot,
+                    id,
+                    trans.next.as_usize(),
+                    &byte_range(trans.start, trans.end),
+                );
+            }
+            State::Sparse(sparse) => {
+                node(&mut dot, id, None);
+                let ranges = sparse
+                    .transitions
+                    .iter()
+                    .map(|trans| (trans.start, trans.end, trans.next));
+                byte_edges(&mut dot, id, ranges, |next| Some(next.as_usize()));
+            }
+            State::Dense(dense) => {
+                node(&mut dot, id, None);
+                let ranges = dense
+                    .transitions
+                    .iter()
+                    .enumerate()
+                    .map(|(byte, next)| (byte as u8, byte as u8, *next));
+                byte_edges(&mut dot, id, ranges, |next| {
+                    Some(next.as_usize()).filter(|_| next != StateID::ZERO)
+                });
+            }
+            State::Look { look, next } => {
+                node(&mut dot, id, None);
+                epsilon(&mut dot, id, next.as_usize(), look_label(*look));
+            }
+            State::Union { alternates } => {
+                node(&mut dot, id, None);
+                for next in alternates.iter() {
+                    epsilon(&mut dot, id, next.as_usize(), "");
+                }
+            }
+            State::BinaryUnion { alt1, alt2 } => {
+                node(&mut dot, id, None);
+                epsilon(&mut dot, id, alt1.as_usize(), "");
+                epsilon(&mut dot, id, alt2.as_usize(), "");
+            }
+            State::Capture {
+                next,
+                group_index,
+                slot,
+                ..
+            } => {
+                node(&mut dot, id, None);
+                // every group has two consecutive slots, its start comes first
+                let label = if slot.as_usize() % 2 == 0 {
+                    format!("open {}", group_index.as_usize())
+                } else {
+                    format!("close {}", group_index.as_usize())
+                };
+                epsilon(&mut dot, id, next.as_usize(), &label);
+            }
+            State::Fail => {
+                let _ = writeln!(dot, "  {} [label=\"{}\\nfail\"];", id, id);
+            }
+            State::Match { pattern_id } => node(&mut dot, id, Some(&[pattern_id.as_usize()])),
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Renders the states of `dfa` reachable from its start state at the
+/// beginning of the text, numbered in the order they are reached. The dead
+/// state is left out, and `EOI` edges lead to the states reached at the end
+/// of the text.
+///
+/// The match states of a dense DFA are delayed by one byte: a match state
+/// means that the text read before the last byte (or the end of the text)
+/// matched.
+fn dfa_to_dot(dfa: &dense::DFA<Vec<u32>>) -> Result<String> {
+    let start = dfa
+        .start_state_forward(&Input::new(""))
+        .map_err(serde_wasm_bindgen::Error::new)?;
+
+    let mut numbers = HashMap::new();
+    let mut queue = VecDeque::new();
+    numbers.insert(start, 0);
+    queue.push_back(start);
+    let mut number_of = |state: StateID, queue: &mut VecDeque<StateID>| {
+        let len = numbers.len();
+        *numbers.entry(state).or_insert_with(|| {
+            queue.push_back(state);
+            len
+        })
+    };
+
+    let mut dot = header();
+    dot.push_str("  start -> 0;\n");
+    while let Some(state) = queue.pop_front() {
+        let id = number_of(state, &mut queue);
+        if dfa.is_match_state(state) {
+            let patterns: Vec<usize> = (0..dfa.match_len(state))
+                .map(|index| dfa.match_pattern(state, index).as_usize())
+                .collect();
+            node(&mut dot, id, Some(&patterns));
+        } else {
+            node(&mut dot, id, None);
+        }
+
+        let mut targets = Vec::with_capacity(256);
+        for byte in 0..=255 {
+            let next = dfa.next_state(state, byte);
+            let next = if dfa.is_dead_state(next) {
+                None
+            } else {
+                Some(number_of(next, &mut queue))
+            };
+            targets.push((byte, byte, next));
+        }
+        byte_edges(&mut dot, id, targets.into_iter(), |next| next);
+
+        let eoi = dfa.next_eoi_state(state);
+        if !dfa.is_dead_state(eoi) {
+            let next = number_of(eoi, &mut queue);
+            edge(&mut dot, id, next, "EOI");
+        }
+    }
+    dot.push_str("}\n");
+    Ok(dot)
+}
+
+fn header() -> String {
+    String::from(concat!(
+        "digraph {\n",
+        "  rankdir=LR;\n",
+        "  node [shape=circle];\n",
+        "  start [shape=point];\n",
+    ))
+}
+
+/// Declares a state, match states list the patterns they match.
+fn node(dot: &mut String, id: usize, patterns: Option<&[usize]>) {
+    match patterns {
+        Some(patterns) => {
+            let patterns: Vec<String> = patterns.iter().map(ToString::to_string).collect();
+            let _ = writeln!(
+                dot,
+                "  {} [shape=doublecircle, label=\"{}\\nmatch {}\"];",
+                id,
+                id,
+                patterns.join(", ")
+            );
+        }
+        None => {
+            let _ = writeln!(dot, "  {};", id);
+        }
+    }
+}
+
+fn edge(dot: &mut String, from: usize, to: usize, label: &str) {
+    let _ = writeln!(dot, "  {} -> {} [label=\"{}\"];", from, to, label);
+}
+
+/// An edge that doesn't consume any input.
+fn epsilon(dot: &mut String, from: usize, to: usize, label: &str) {
+    let _ = writeln!(
+        dot,
+        "  {} -> {} [style=dashed, label=\"{}\"];",
+        from, to, label
+    );
+}
+
+/// Adds one edge per target of the byte `ranges`, labeled with all the
+/// ranges leading to it, so a class like `[a-z0-9]` is a single edge.
+/// `target` returns the state a range leads to, or `None` for no edge.
+fn byte_edges<T, I, F>(dot: &mut String, from: usize, ranges: I, target: F)
+where
+    T: Copy,
+    I: Iterator<Item = (u8, u8, T)>,
+    F: Fn(T) -> Option<usize>,
+{
+    let mut merged: Vec<(u8, u8, usize)> = Vec::new();
+    for (start, end, next) in ranges {
+        let next = match target(next) {
+            Some(next) => next,
+            None => continue,
+        };
+        match merged.last_mut() {
+            Some(last) if last.2 == next && last.1 as u16 + 1 == start as u16 => last.1 = end,
+            _ => merged.push((start, end, next)),
+        }
+    }
+
+    let mut order = Vec::new();
+    let mut labels: HashMap<usize, Vec<String>> = HashMap::new();
+    for (start, end, next) in merged {
+        labels
+            .entry(next)
+            .or_insert_with(|| {
+                order.push(next);
+                Vec::new()
+            })
+            .push(byte_range(start, end));
+    }
+    for next in order {
+        edge(dot, from, next, &labels[&next].join(", "));
+    }
+}
+
+fn byte_range(start: u8, end: u8) -> String {
+    if start == end {
+        byte(start)
+    } else {
+        format!("{}-{}", byte(start), byte(end))
+    }
+}
+
+/// Escapes a byte for a DOT label: printable ASCII characters are kept,
+/// other bytes are written `\xNN`.
+fn byte(byte: u8) -> String {
+    match byte {
+        b'"' => String::from("\\\""),
+        b'\\' => String::from("\\\\"),
+        b'!'..=b'~' => char::from(byte).to_string(),
+        _ => format!("\\\\x{:02X}", byte),
+    }
+}
+
+/// Writes an assertion the way it's written in a pattern, with the flags it
+/// depends on.
+fn look_label(look: Look) -> &'static str {
+    match look {
+        Look::Start => "\\\\A",
+        Look::End => "\\\\z",
+        Look::StartLF => "(?m:^)",
+        Look::EndLF => "(?m:$)",
+        Look::StartCRLF => "(?mR:^)",
+        Look::EndCRLF => "(?mR:$)",
+        Look::WordAscii => "(?-u:\\\\b)",
+        Look::WordAsciiNegate => "(?-u:\\\\B)",
+        Look::WordUnicode => "\\\\b",
+        Look::WordUnicodeNegate => "\\\\B",
+        Look::WordStartAscii => "(?-u:\\\\b{start})",
+        Look::WordEndAscii => "(?-u:\\\\b{end})",
+        Look::WordStartUnicode => "\\\\b{start}",
+        Look::WordEndUnicode => "\\\\b{end}",
+        Look::WordStartHalfAscii => "(?-u:\\\\b{start-half})",
+        Look::WordEndHalfAscii => "(?-u:\\\\b{end-half})",
+        Look::WordStartHalfUnicode => "\\\\b{start-half}",
+        Look::WordEndHalfUnicode => "\\\\b{end-half}",
+    }
+}
@@ -0,0 +1,617 @@
+use regex_automata::dfa::{dense, regex as dfa_regex, StartKind};
+use regex_automata::hybrid::{dfa as hybrid_dfa, regex as hybrid_regex};
+use regex_automata::nfa::thompson::{self, backtrack, pikevm, WhichCaptures, NFA};
+use regex_automata::util::captures::Captures;
+use regex_automata::util::iter;
+use regex_automata::util::prefilter::Prefilter;
+use regex_automata::util::primitives::{NonMaxUsize, PatternID};
+use regex_automata::{dfa::onepass, Anchored, Input, Match, MatchError, MatchErrorKind, MatchKind};
+use regex_syntax::hir::literal::{ExtractKind, Extractor};
+use regex_syntax::hir::Hir;
+use serde::Serialize;
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+
+use crate::dfa;
+use crate::options::{RegexEngine, RegexOptions};
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+#[wasm_bindgen(typescript_custom_section)]
+const REGEX_INFO_TYPE: &'static str = r#"/**
+ * How a regular expression was compiled, see `RRegex.info`.
+ */
+export type RegexInfo = {
+  /** The engine selected with the `engine` option. */
+  engine: RegexEngine
+
+  /**
+   * The engines built to search, e.g. `["hybrid", "pikevm"]` when capture
+   * groups are resolved with the PikeVM. With the `auto` engine, the regex
+   * crate builds its engines internally and doesn't report them, so this
+   * is `["meta"]`.
+   */
+  engines: string[]
+
+  /** The number of states of the Thompson NFA every engine is built from. */
+  nfaStates: number
+
+  /**
+   * The heap memory used by the NFAs, the prefilter, the engines and their
+   * caches, in bytes. The cache of a lazy DFA grows while searching. With
+   * the `auto` engine, only the NFA and the prefilter are counted.
+   */
+  memoryUsage: number
+
+  /**
+   * The prefilter used to skip to the candidates of a match, when the
+   * pattern starts with a small set of literals.
+   */
+  prefilter?: PrefilterInfo
+}
+
+/**
+ * A prefilter that searches for the literals every match starts with.
+ */
+export type PrefilterInfo = {
+  /**
+   * The algorithm used to search the literals: `memchr`, `memchr2` or
+   * `memchr3` for one to three single bytes, `memmem` for a single literal,
+   * `byteset` for more single bytes and `aho-corasick` for the others.
+   */
+  kind: 'memchr' | 'memchr2' | 'memchr3' | 'memmem' | 'byteset' | 'aho-corasick'
+
+  /** The literals searched for. */
+  literals: string[]
+
+  /**
+   * Whether the prefilter is expected to be faster than the engine alone,
+   * slow prefilters are only used to start searches.
+   */
+  fast: boolean
+}"#;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegexInfo {
+    engine: RegexEngine,
+    engines: Vec<&'static str>,
+    nfa_states: usize,
+    memory_usage: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prefilter: Option<PrefilterInfo>,
+}
+
+#[derive(Serialize)]
+struct PrefilterInfo {
+    kind: &'static str,
+    literals: Vec<String>,
+    fast: bool,
+}
+
+/// A prefilter, with the literals it was built from.
+struct LiteralPrefilter {
+    prefilter: Prefilter,
+    literals: Vec<Vec<u8>>,
+}
+
+/// A regex engine of `regex-automata` with its cache, for the engines that
+/// only find the bounds of matches a PikeVM resolves the capture groups.
+// a single searcher is built per regular expression, so its size is moot
+#[allow(clippy::large_enum_variant)]
+enum Searcher {
+    PikeVm(pikevm::PikeVM, pikevm::Cache),
+    Backtrack(backtrack::BoundedBacktracker, backtrack::Cache),
+    OnePass(onepass::DFA, onepass::Cache),
+    Hybrid(
+        hybrid_regex::Regex,
+        hybrid_regex::Cache,
+        pikevm::PikeVM,
+        pikevm::Cache,
+    ),
+    Dfa(dfa_regex::Regex, pikevm::PikeVM, pikevm::Cache),
+}
+
+/// A regular expression searched with a single engine of `regex-automata`,
+/// instead of the combination of engines the regex crate picks from.
+pub struct Engine {
+    kind: RegexEngine,
+    nfa: NFA,
+    reverse_nfa: Option<NFA>,
+    prefilter: Option<LiteralPrefilter>,
+    searcher: RefCell<Searcher>,
+}
+
+impl Engine {
+    /// Builds the `kind` engine for `hir`, which can't be `auto`.
+    pub fn new(hir: &Hir, kind: RegexEngine, options: &RegexOptions) -> Result<Engine> {
+        let nfa = compile(hir, options, false)?;
+        let prefilter = prefilter(hir, &nfa);
+        let pre = prefilter.as_ref().map(|pre| pre.prefilter.clone());
+
+        let mut reverse_nfa = None;
+        let searcher = match kind {
+            RegexEngine::Auto => {
+                return Err(serde_wasm_bindgen::Error::new(
+                    "the auto engine is built by the regex crate",
+                ))
+            }
+            RegexEngine::PikeVm => {
+                let vm = pikevm(&nfa, pre)?;
+                let cache = vm.create_cache();
+                Searcher::PikeVm(vm, cache)
+            }
+            RegexEngine::Backtrack => {
+                let backtracker = backtrack::Builder::new()
+                    .configure(backtrack::Config::new().prefilter(pre))
+                    .build_from_nfa(nfa.clone())
+                    .map_err(serde_wasm_bindgen::Error::new)?;
+                let cache = backtracker.create_cache();
+                Searcher::Backtrack(backtracker, cache)
+            }
+            RegexEngine::OnePass => {
+                if !nfa.is_always_start_anchored() {
+                    return Err(serde_wasm_bindgen::Error::new(
+                        "the onepass engine only supports patterns anchored at the start, with ^ or \\A",
+                    ));
+                }
+                let dfa = onepass::Builder::new()
+                    .build_from_nfa(nfa.clone())
+                    .map_err(serde_wasm_bindgen::Error::new)?;
+                let cache = dfa.create_cache();
+                Searcher::OnePass(dfa, cache)
+            }
+            RegexEngine::Hybrid => {
+                let reverse = compile(hir, options, true)?;
+                let mut config = hybrid_dfa::Config::new()
+                    .prefilter(pre)
+                    .unicode_word_boundary(true);
+                if let Some(limit) = options.dfa_size_limit {
+                    config = config.cache_capacity(limit);
+                }
+                let forward_dfa = hybrid_dfa::Builder::new()
+                    .configure(config.clone())
+                    .build_from_nfa(nfa.clone())
+                    .map_err(serde_wasm_bindgen::Error::new)?;
+                let reverse_dfa = hybrid_dfa::Builder::new()
+                    .configure(
+                        config
+                            .prefilter(None)
+                            .specialize_start_states(false)
+                            .match_kind(MatchKind::All),
+                    )
+                    .build_from_nfa(reverse.clone())
+                    .map_err(serde_wasm_bindgen::Error::new)?;
+                reverse_nfa = Some(reverse);
+                let regex = hybrid_regex::Builder::new().build_from_dfas(forward_dfa, reverse_dfa);
+                let cache = regex.create_cache();
+                let vm = pikevm(&nfa, None)?;
+                let vm_cache = vm.create_cache();
+                Searcher::Hybrid(regex, cache, vm, vm_cache)
+            }
+            RegexEngine::Dfa => {
+                let reverse = compile(hir, options, true)?;
+                let mut config = dense::Config::new()
+                    .prefilter(pre)
+                    .start_kind(StartKind::Both)
+                    .unicode_word_boundary(true);
+                if let Some(limit) = options.dfa_size_limit {
+                    config = config
+                        .dfa_size_limit(Some(limit))
+                        .determinize_size_limit(Some(limit));
+                }
+                let forward_dfa = dense::Builder::new()
+                    .configure(config.clone())
+                    .build_from_nfa(&nfa)
+                    .map_err(serde_wasm_bindgen::Error::new)?;
+                let reverse_dfa = dense::Builder::new()
+                    .configure(
+                        config
+                            .prefilter(None)
+                            .specialize_start_states(false)
+                            .start_kind(StartKind::Anchored)
+                            .match_kind(MatchKind::All),
+                    )
+                    .build_from_nfa(&reverse)
+                    .map_err(serde_wasm_bindgen::Error::new)?;
+                reverse_nfa = Some(reverse);
+                dfa_searcher(&nfa, forward_dfa, reverse_dfa)?
+            }
+        };
+
+        Ok(Engine {
+            kind,
+            nfa,
+            reverse_nfa,
+            prefilter,
+            searcher: RefCell::new(searcher),
+        })
+    }
+
+    /// Builds the `dfa` engine for `hir` from DFAs serialized with
+    /// `dfa_bytes`, skipping their determinization. The bytes are checked
+    /// to be valid DFAs, but not to be built from `hir` with `options`.
+    ///
+    /// Serialized DFAs don't keep their prefilter, so it's left out.
+    pub fn from_dfa_bytes(
+        hir: &Hir,
+        options: &RegexOptions,
+        forward: &[u8],
+        reverse: &[u8],
+    ) -> Result<Engine> {
+        let nfa = compile(hir, options, false)?;
+        let forward = dfa::from_bytes(forward)?;
+        let reverse = dfa::from_bytes(reverse)?;
+        let searcher = dfa_searcher(&nfa, forward, reverse)?;
+        Ok(Engine {
+            kind: RegexEngine::Dfa,
+            nfa,
+            reverse_nfa: None,
+            prefilter: None,
+            searcher: RefCell::new(searcher),
+        })
+    }
+
+    /// Returns the forward and reverse DFAs of the `dfa` engine, serialized
+    /// with `dfa::to_bytes`, or `None` for the other engines.
+    pub fn dfa_bytes(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+        match &*self.searcher.borrow() {
+            Searcher::Dfa(regex, _, _) => Some((
+                dfa::to_bytes(regex.forward()),
+                dfa::to_bytes(regex.reverse()),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Returns how many times the caches of the lazy DFAs were cleared
+    /// because they were full, `0` for the other engines.
+    pub fn cache_clears(&self) -> usize {
+        match &mut *self.searcher.borrow_mut() {
+            Searcher::Hybrid(_, cache, _, _) => {
+                cache.forward().clear_count() + cache.reverse().clear_count()
+            }
+            _ => 0,
+        }
+    }
+
+    /// Describes the engines built for this regular expression.
+    pub fn info(&self) -> RegexInfo {
+        let searcher = self.searcher.borrow();
+        let (engines, memory_usage) = match &*searcher {
+            Searcher::PikeVm(_, cache) => (vec!["pikevm"], cache.memory_usage()),
+            Searcher::Backtrack(_, cache) => (vec!["backtrack"], cache.memory_usage()),
+            Searcher::OnePass(dfa, cache) => {
+                (vec!["onepass"], dfa.memory_usage() + cache.memory_usage())
+            }
+            Searcher::Hybrid(regex, cache, _, vm_cache) => (
+                vec!["hybrid", "pikevm"],
+                regex.forward().memory_usage()
+                    + regex.reverse().memory_usage()
+                    + cache.memory_usage()
+                    + vm_cache.memory_usage(),
+            ),
+            Searcher::Dfa(regex, _, vm_cache) => (
+                vec!["dfa", "pikevm"],
+                regex.forward().memory_usage()
+                    + regex.reverse().memory_usage()
+                    + vm_cache.memory_usage(),
+            ),
+        };
+        let nfa_memory_usage =
+            self.nfa.memory_usage() + self.reverse_nfa.as_ref().map_or(0, NFA::memory_usage);
+        describe(
+            self.kind,
+            engines,
+            &self.nfa,
+            nfa_memory_usage + memory_usage,
+            self.prefilter.as_ref(),
+        )
+    }
+
+    /// Finds the bounds of the leftmost-first match in `input`.
+    pub fn search(&self, input: &Input) -> std::result::Result<Option<Match>, MatchError> {
+        match &mut *self.searcher.borrow_mut() {
+            Searcher::PikeVm(vm, cache) => bounds(|slots| Ok(vm.search_slots(cache, input, slots))),
+            Searcher::Backtrack(backtracker, cache) => {
+                bounds(|slots| backtracker.try_search_slots(cache, input, slots))
+            }
+            Searcher::OnePass(dfa, cache) => {
+                bounds(|slots| dfa.try_search_slots(cache, input, slots))
+            }
+            Searcher::Hybrid(regex, cache, vm, vm_cache) => {
+                or_pikevm(regex.try_search(cache, input), vm, vm_cache, input)
+            }
+            Searcher::Dfa(regex, vm, vm_cache) => {
+                or_pikevm(regex.try_search(input), vm, vm_cache, input)
+            }
+        }
+    }
+
+    /// Finds the leftmost-first match in `input` and its capture groups.
+    pub fn search_captures(
+        &self,
+        input: &Input,
+        captures: &mut Captures,
+    ) -> std::result::Result<(), MatchError> {
+        let mut searcher = self.searcher.borrow_mut();
+        let (m, vm, vm_cache) = match &mut *searcher {
+            Searcher::PikeVm(vm, cache) => {
+                vm.search(cache, input, captures);
+                return Ok(());
+            }
+            Searcher::Backtrack(backtracker, cache) => {
+                return backtracker.try_search(cache, input, captures)
+            }
+            Searcher::OnePass(dfa, cache) => return dfa.try_search(cache, input, captures),
+            Searcher::Hybrid(regex, cache, vm, vm_cache) => {
+                let m = or_pikevm(regex.try_search(cache, input), vm, vm_cache, input)?;
+                (m, &*vm, vm_cache)
+            }
+            Searcher::Dfa(regex, vm, vm_cache) => {
+                let m = or_pikevm(regex.try_search(input), vm, vm_cache, input)?;
+                (m, &*vm, vm_cache)
+            }
+        };
+        match m {
+            Some(m) => {
+                // the match is already known, only its groups are missing
+                let input = input.clone().span(m.range()).anchored(Anchored::Yes);
+                vm.search(vm_cache, &input, captures);
+                Ok(())
+            }
+            None => {
+                captures.set_pattern(None);
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns an empty set of capture groups for `search_captures`.
+    pub fn create_captures(&self) -> Captures {
+        Captures::all(self.nfa.group_info().clone())
+    }
+
+    /// Calls `f` with every successive non-overlapping match in `text`,
+    /// until it returns `false`.
+    pub fn each_match<F>(&self, text: &str, mut f: F) -> Result<()>
+    where
+        F: FnMut(Match) -> bool,
+    {
+        let mut searcher = iter::Searcher::new(Input::new(text));
+        while let Some(m) = searcher
+            .try_advance(|input| self.search(input))
+            .map_err(serde_wasm_bindgen::Error::new)?
+        {
+            if !f(m) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls `f` with the capture groups of every successive non-overlapping
+    /// match in `text`, until it returns `false`.
+    pub fn each_captures<F>(&self, text: &str, mut f: F) -> Result<()>
+    where
+        F: FnMut(&Captures) -> bool,
+    {
+        let mut captures = self.create_captures();
+        let mut searcher = iter::Searcher::new(Input::new(text));
+        while searcher
+            .try_advance(|input| {
+                self.search_captures(input, &mut captures)?;
+                Ok(captures.get_match())
+            })
+            .map_err(serde_wasm_bindgen::Error::new)?
+            .is_some()
+        {
+            if !f(&captures) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the first match in `text` at or after `start`.
+    pub fn find_at(&self, text: &str, start: usize) -> Result<Option<Match>> {
+        self.search(&Input::new(text).range(start..))
+            .map_err(serde_wasm_bindgen::Error::new)
+    }
+
+    /// Returns the end of the first match found in `text` at or after
+    /// `start`, without looking for the end of the leftmost-first match.
+    pub fn shortest_match_at(&self, text: &str, start: usize) -> Result<Option<usize>> {
+        let m = self
+            .search(&Input::new(text).range(start..).earliest(true))
+            .map_err(serde_wasm_bindgen::Error::new)?;
+        Ok(m.map(|m| m.end()))
+    }
+
+    /// Returns the capture groups of the first match in `text`.
+    pub fn captures(&self, text: &str) -> Result<Option<Captures>> {
+        let mut captures = self.create_captures();
+        self.search_captures(&Input::new(text), &mut captures)
+            .map_err(serde_wasm_bindgen::Error::new)?;
+        Ok(Some(captures).filter(Captures::is_match))
+    }
+
+    /// Replaces at most `limit` matches in `text` (all of them when `limit`
+    /// is `0`), expanding `$name` in `rep` like the regex crate does, and
+    /// returns how many were replaced.
+    pub fn replacen(&self, text: &str, limit: usize, rep: &str) -> Result<(String, usize)> {
+        let mut replaced = String::with_capacity(text.len());
+        let mut last = 0;
+        let mut count = 0;
+        self.each_captures(text, |captures| {
+            let m = captures.get_match().expect("captures of a match");
+            replaced.push_str(&text[last..m.start()]);
+            captures.interpolate_string_into(text, rep, &mut replaced);
+            last = m.end();
+            count += 1;
+            limit == 0 || count < limit
+        })?;
+        replaced.push_str(&text[last..]);
+        Ok((replaced, count))
+    }
+
+    /// Splits `text` by the matches, into at most `limit` substrings when
+    /// given, the last one being the rest of `text`.
+    pub fn splitn(&self, text: &str, limit: Option<usize>) -> Result<Vec<String>> {
+        let mut substrings = Vec::new();
+        if limit == Some(0) {
+            return Ok(substrings);
+        }
+        let mut last = 0;
+        self.each_match(text, |m| {
+            if limit.is_some_and(|limit| substrings.len() + 1 == limit) {
+                return false;
+            }
+            substrings.push(text[last..m.start()].to_owned());
+            last = m.end();
+            true
+        })?;
+        substrings.push(text[last..].to_owned());
+        Ok(substrings)
+    }
+}
+
+/// Describes the `auto` engine, where the regex crate builds the engines
+/// from the same NFA.
+pub fn auto_info(hir: &Hir, options: &RegexOptions) -> Result<RegexInfo> {
+    let nfa = compile(hir, options, false)?;
+    let prefilter = prefilter(hir, &nfa);
+    Ok(describe(
+        RegexEngine::Auto,
+        vec!["meta"],
+        &nfa,
+        nfa.memory_usage(),
+        prefilter.as_ref(),
+    ))
+}
+
+fn describe(
+    engine: RegexEngine,
+    engines: Vec<&'static str>,
+    nfa: &NFA,
+    memory_usage: usize,
+    prefilter: Option<&LiteralPrefilter>,
+) -> RegexInfo {
+    RegexInfo {
+        engine,
+        engines,
+        nfa_states: nfa.states().len(),
+        memory_usage: memory_usage + prefilter.map_or(0, |pre| pre.prefilter.memory_usage()),
+        prefilter: prefilter.map(|pre| PrefilterInfo {
+            kind: prefilter_kind(&pre.literals),
+            literals: pre
+                .literals
+                .iter()
+                .map(|literal| String::from_utf8_lossy(literal).into_owned())
+                .collect(),
+            fast: pre.prefilter.is_fast(),
+        }),
+    }
+}
+
+/// Compiles `hir` into a Thompson NFA, like the regex crate does, or into
+/// a reverse NFA without capture groups for the DFAs that find the start of
+/// matches.
+fn compile(hir: &Hir, options: &RegexOptions, reverse: bool) -> Result<NFA> {
+    let mut config = thompson::Config::new();
+    if let Some(limit) = options.size_limit {
+        config = config.nfa_size_limit(Some(limit));
+    }
+    if reverse {
+        config = config.reverse(true).which_captures(WhichCaptures::None);
+    }
+    thompson::Compiler::new()
+        .configure(config)
+        .build_from_hir(hir)
+        .map_err(serde_wasm_bindgen::Error::new)
+}
+
+/// Searches with full DFAs, resolving capture groups with a PikeVM.
+fn dfa_searcher(nfa: &NFA, forward: dfa::Dfa, reverse: dfa::Dfa) -> Result<Searcher> {
+    let regex = dfa_regex::Builder::new().build_from_dfas(forward, reverse);
+    let vm = pikevm(nfa, None)?;
+    let vm_cache = vm.create_cache();
+    Ok(Searcher::Dfa(regex, vm, vm_cache))
+}
+
+fn pikevm(nfa: &NFA, pre: Option<Prefilter>) -> Result<pikevm::PikeVM> {
+    pikevm::Builder::new()
+        .configure(pikevm::Config::new().prefilter(pre))
+        .build_from_nfa(nfa.clone())
+        .map_err(serde_wasm_bindgen::Error::new)
+}
+
+/// Builds a prefilter from the literals every match starts with, like the
+/// regex crate does, unless the pattern is anchored at the start.
+fn prefilter(hir: &Hir, nfa: &NFA) -> Option<LiteralPrefilter> {
+    if nfa.is_always_start_anchored() {
+        return None;
+    }
+    let mut extractor = Extractor::new();
+    extractor.kind(ExtractKind::Prefix);
+    let mut prefixes = extractor.extract(hir);
+    prefixes.optimize_for_prefix_by_preference();
+    let literals: Vec<Vec<u8>> = prefixes
+        .literals()?
+        .iter()
+        .map(|literal| literal.as_bytes().to_vec())
+        .collect();
+    let prefilter = Prefilter::new(MatchKind::LeftmostFirst, &literals)?;
+    Some(LiteralPrefilter {
+        prefilter,
+        literals,
+    })
+}
+
+/// Names the algorithm `Prefilter::new` picks for `literals`, which
+/// `regex-automata` doesn't expose: the first one that supports them,
+/// among `memchr`, `memchr2` and `memchr3` for up to three single bytes,
+/// `memmem` for a single literal, `byteset` for single bytes and
+/// `aho-corasick`. The SIMD searchers it prefers to `byteset` are not built
+/// for WebAssembly, so the choice only depends on the literals.
+fn prefilter_kind(literals: &[Vec<u8>]) -> &'static str {
+    let bytes = literals.iter().all(|literal| literal.len() == 1);
+    match literals.len() {
+        1 if bytes => "memchr",
+        2 if bytes => "memchr2",
+        3 if bytes => "memchr3",
+        1 => "memmem",
+        _ if bytes => "byteset",
+        _ => "aho-corasick",
+    }
+}
+
+/// Searches with the PikeVM when a DFA stopped at a byte it can't handle,
+/// like a non-ASCII character next to a Unicode word boundary.
+fn or_pikevm(
+    result: std::result::Result<Option<Match>, MatchError>,
+    vm: &pikevm::PikeVM,
+    cache: &mut pikevm::Cache,
+    input: &Input,
+) -> std::result::Result<Option<Match>, MatchError> {
+    match result {
+        Err(err) if matches!(err.kind(), MatchErrorKind::Quit { .. }) => {
+            bounds(|slots| Ok(vm.search_slots(cache, input, slots)))
+        }
+        result => result,
+    }
+}
+
+/// Runs a search that only needs the slots of the whole match.
+fn bounds<F>(search: F) -> std::result::Result<Option<Match>, MatchError>
+where
+    F: FnOnce(&mut [Option<NonMaxUsize>]) -> std::result::Result<Option<PatternID>, MatchError>,
+{
+    let mut slots = [None, None];
+    let pid = search(&mut slots)?;
+    Ok(pid.and_then(|pid| {
+        let start = slots[0]?.get();
+        let end = slots[1]?.get();
+        Some(Match::new(pid, start..end))
+    }))
+}
@@ -0,0 +1,240 @@
+use regex_syntax::ast;
+use serde::Serialize;
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const PATTERN_ERROR_TYPE: &'static str = r#"/**
+ * The error thrown when a pattern can't be translated from or to another
+ * regular expression dialect.
+ *
+ * Besides the human readable `message`, it carries the location of the
+ * offending construct in the original pattern, so it can be highlighted in
+ * an editor.
+ *
+ * ```typescript
+ * import { RRegex } from "rregex"
+ *
+ * try {
+ *   RRegex.toJsRegExp("(?<a.b>x)")
+ * } catch (err) {
+ *   expect(err.kind).toBe("Unsupported")
+ *   expect(err.span.start.offset).toBe(3)
+ *   expect(err.span.end.offset).toBe(6)
+ * }
+ * ```
+ */
+export type PatternError = Error & {
+  /**
+   * `Syntax` when the pattern is not valid in its own dialect, and
+   * `Unsupported` when the pattern is valid but uses a construct that has no
+   * faithful equivalent in the target dialect.
+   */
+  kind: 'Syntax' | 'Unsupported'
+
+  /** The pattern in which the error occurred. */
+  pattern: string
+
+  /** The location of the error in `pattern`. */
+  span: Span
+}
+
+/**
+ * A span of a pattern, the `end` position is exclusive.
+ */
+export type Span = {
+  start: Position
+  end: Position
+}
+
+/**
+ * A single position in a pattern.
+ */
+export type Position = {
+  /** The absolute offset of this position, in bytes, starting at `0`. */
+  offset: number
+
+  /** The line number, starting at `1`. */
+  line: number
+
+  /**
+   * The approximate column number (counted in code points), starting at `1`.
+   */
+  column: number
+}"#;
+
+#[derive(Clone, Copy)]
+pub enum PatternErrorKind {
+    Syntax,
+    Unsupported,
+}
+
+/// An error located in the pattern that caused it, see `PatternError` in the
+/// typescript definitions.
+pub struct PatternError {
+    kind: PatternErrorKind,
+    message: String,
+    pattern: String,
+    span: ast::Span,
+}
+
+impl PatternError {
+    pub fn new<M: ToString>(
+        kind: PatternErrorKind,
+        message: M,
+        pattern: &str,
+        span: ast::Span,
+    ) -> PatternError {
+        PatternError {
+            kind,
+            message: message.to_string(),
+            pattern: pattern.to_owned(),
+            span,
+        }
+    }
+
+    /// Creates an error spanning the bytes `start..end` of `pattern`.
+    pub fn at<M: ToString>(
+        kind: PatternErrorKind,
+        message: M,
+        pattern: &str,
+        start: usize,
+        end: usize,
+    ) -> PatternError {
+        let span = ast::Span::new(position(pattern, start), position(pattern, end));
+        PatternError::new(kind, message, pattern, span)
+    }
+
+    pub fn syntax<M: ToString>(message: M, pattern: &str, start: usize, end: usize) -> Self {
+        PatternError::at(PatternErrorKind::Syntax, message, pattern, start, end)
+    }
+
+    pub fn unsupported<M: ToString>(message: M, pattern: &str, start: usize, end: usize) -> Self {
+        PatternError::at(PatternErrorKind::Unsupported, message, pattern, start, end)
+    }
+}
+
+/// Returns the line/column position of the byte `offset` in `pattern`.
+fn position(pattern: &str, offset: usize) -> ast::Position {
+    let offset = offset.min(pattern.len());
+    let before = &pattern[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = match before.rfind('\n') {
+        Some(newline) => before[newline + 1..].chars().count() + 1,
+        None => before.chars().count() + 1,
+    };
+    ast::Position::new(offset, line, column)
+}
+
+impl From<regex_syntax::Error> for PatternError {
+    fn from(err: regex_syntax::Error) -> Self {
+        match err {
+            regex_syntax::Error::Parse(err) => err.into(),
+            regex_syntax::Error::Translate(err) => err.into(),
+            err => PatternError::syntax(err, "", 0, 0),
+        }
+    }
+}
+
+impl From<ast::Error> for PatternError {
+    fn from(err: ast::Error) -> Self {
+        PatternError::new(
+            PatternErrorKind::Syntax,
+            err.kind(),
+            err.pattern(),
+            *err.span(),
+        )
+    }
+}
+
+impl From<regex_syntax::hir::Error> for PatternError {
+    fn from(err: regex_syntax::hir::Error) -> Self {
+        PatternError::new(
+            PatternErrorKind::Syntax,
+            err.kind(),
+            err.pattern(),
+            *err.span(),
+        )
+    }
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "regex parse error:")?;
+        if self.span.is_one_line() && !self.pattern.contains('\n') {
+            let start = self.span.start.column - 1;
+            let width = (self.span.end.column - self.span.start.column).max(1);
+            writeln!(f, "    {}", self.pattern)?;
+            writeln!(f, "    {}{}", " ".repeat(start), "^".repeat(width))?;
+        } else {
+            writeln!(
+                f,
+                "on line {} (column {}) through line {} (column {})",
+                self.span.start.line,
+                self.span.start.column,
+                self.span.end.line,
+                self.span.end.column
+            )?;
+        }
+        write!(f, "error: {}", self.message)
+    }
+}
+
+/// A serializable `ast::Span`, see `Span` in the typescript definitions.
+#[derive(Serialize)]
+pub struct Span {
+    start: Position,
+    end: Position,
+}
+
+#[derive(Serialize)]
+struct Position {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl From<&ast::Span> for Span {
+    fn from(value: &ast::Span) -> Self {
+        Span {
+            start: Position::from(&value.start),
+            end: Position::from(&value.end),
+        }
+    }
+}
+
+impl From<&ast::Position> for Position {
+    fn from(value: &ast::Position) -> Self {
+        Position {
+            offset: value.offset,
+            line: value.line,
+            column: value.column,
+        }
+    }
+}
+
+impl From<PatternError> for serde_wasm_bindgen::Error {
+    fn from(value: PatternError) -> Self {
+        let err = js_sys::Error::new(&value.to_string());
+        let kind = match value.kind {
+            PatternErrorKind::Syntax => "Syntax",
+            PatternErrorKind::Unsupported => "Unsupported",
+        };
+        let span = Span::from(&value.span);
+
+        let properties = [
+            ("kind", JsValue::from(kind)),
+            ("pattern", JsValue::from(value.pattern.as_str())),
+            (
+                "span",
+                serde_wasm_bindgen::to_value(&span).unwrap_or(JsValue::UNDEFINED),
+            ),
+        ];
+        for (key, property) in properties.iter() {
+            // setting a property on a fresh `Error` object can't fail
+            let _ = js_sys::Reflect::set(&err, &JsValue::from(*key), property);
+        }
+
+        serde_wasm_bindgen::Error::from(JsValue::from(err))
+    }
+}
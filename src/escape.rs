@@ -1,5 +1,12 @@
+use regex_syntax::hir::HirKind;
+use regex_syntax::is_meta_character;
 use wasm_bindgen::prelude::*;
 
+use crate::options::RegexOptions;
+use crate::types;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
 /// Escapes all regular expression meta characters in `text`.
 ///
 /// The string returned may be safely used as a literal in a regular
@@ -7,4 +14,108 @@ use wasm_bindgen::prelude::*;
 #[wasm_bindgen]
 pub fn escape(text: &str) -> String {
   regex::escape(text)
-}
\ No newline at end of file
+}
+
+/// Escapes the characters of `text` that have a meaning inside a character
+/// class (`\`, `[`, `]`, `^`, `-`, and the `&`, `~` of set operations).
+///
+/// The string returned may be safely used between the brackets of a
+/// character class, e.g. `[${escapeClass(chars)}]+`.
+#[wasm_bindgen(js_name = escapeClass)]
+pub fn escape_class(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    if matches!(c, '\\' | '[' | ']' | '^' | '-' | '&' | '~') {
+      escaped.push('\\');
+    }
+    escaped.push(c);
+  }
+  escaped
+}
+
+/// Escapes all regular expression meta characters in `text`, like
+/// `escape`, and writes every character that is not printable ASCII as a
+/// `\x{...}` escape, so the pattern can be embedded in ASCII only sources.
+#[wasm_bindgen(js_name = escapeAscii)]
+pub fn escape_ascii(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    if is_meta_character(c) {
+      escaped.push('\\');
+      escaped.push(c);
+    } else if c == ' ' || c.is_ascii_graphic() {
+      escaped.push(c);
+    } else {
+      escaped.push_str(&format!("\\x{{{:X}}}", c as u32));
+    }
+  }
+  escaped
+}
+
+/// Escapes arbitrary bytes as a literal for engines that search bytes
+/// instead of strings, like `regex::bytes` in Rust.
+///
+/// Bytes that are not printable ASCII are written as `\xHH` escapes, and
+/// the pattern is wrapped in `(?-u:...)` when some of them are not ASCII,
+/// so they match a single byte instead of a code point. Note that `RRegex`
+/// searches strings, so it rejects patterns matching invalid UTF-8.
+///
+/// @param {Uint8Array} bytes - The bytes to escape
+/// @returns {string}
+#[wasm_bindgen(skip_jsdoc, js_name = escapeBytes)]
+pub fn escape_bytes(bytes: &[u8]) -> String {
+  let mut escaped = String::with_capacity(bytes.len());
+  for &byte in bytes {
+    let c = char::from(byte);
+    if is_meta_character(c) {
+      escaped.push('\\');
+      escaped.push(c);
+    } else if c == ' ' || c.is_ascii_graphic() {
+      escaped.push(c);
+    } else {
+      escaped.push_str(&format!("\\x{:02X}", byte));
+    }
+  }
+  if bytes.is_ascii() {
+    escaped
+  } else {
+    format!("(?-u:{})", escaped)
+  }
+}
+
+/// Returns the string matched by `pattern` when it only matches that
+/// string, e.g. `a\.b` or `\x{61}bc`, the reverse of `escape`. Throws when
+/// the pattern has anything else than literals, including case insensitive
+/// literals, which match more than one string.
+///
+/// # Example
+///
+/// ```typescript
+/// import { escape, unescape } from "rregex"
+///
+/// expect(unescape("https://example\\.com/\\?q=1")).toBe("https://example.com/?q=1")
+/// expect(unescape(escape("1 + 1 = 2"))).toBe("1 + 1 = 2")
+/// expect(() => unescape("a+")).toThrow()
+/// ```
+///
+/// @param {string} pattern - The regular expression
+/// @param {RegexOptions} options - The options used to parse the regular expression
+/// @returns {string}
+#[wasm_bindgen(skip_jsdoc)]
+pub fn unescape(pattern: &str, options: JsValue) -> Result<String> {
+  let options: RegexOptions = types::options(options)?;
+  let hir = options
+    .parser()
+    .parse(pattern)
+    .map_err(serde_wasm_bindgen::Error::new)?;
+  match hir.kind() {
+    HirKind::Empty => Ok(String::new()),
+    HirKind::Literal(literal) => String::from_utf8(literal.0.to_vec()).map_err(|_| {
+      serde_wasm_bindgen::Error::new("pattern matches bytes that are not valid UTF-8")
+    }),
+    _ => Err(serde_wasm_bindgen::Error::new(format!(
+      "pattern is not a literal: {}",
+      pattern
+    ))),
+  }
+}
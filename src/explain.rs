@@ -0,0 +1,624 @@
+use regex_syntax::ast::{self, Ast};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::error::{PatternError, Span};
+use crate::options::RegexOptions;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+#[wasm_bindgen(typescript_custom_section)]
+const EXPLAIN_NODE_TYPE: &'static str = r#"/**
+ * A node of the explanation of a pattern, see `RRegex.explain`.
+ */
+export type ExplainNode = {
+  /** The kind of syntax the node explains. */
+  kind:
+    | 'empty'
+    | 'flags'
+    | 'literal'
+    | 'dot'
+    | 'assertion'
+    | 'class'
+    | 'range'
+    | 'repetition'
+    | 'group'
+    | 'alternation'
+    | 'concat'
+
+  /**
+   * A plain English description of the node, like `exactly 5 times` or
+   * `one character from: "0" to "9" or "a" to "f"`.
+   */
+  description: string
+
+  /** The part of the pattern the node explains. */
+  source: string
+
+  /** The location of `source` in the pattern. */
+  span: Span
+
+  /** The nodes this node is made of, e.g. the alternatives of an alternation. */
+  children: ExplainNode[]
+}"#;
+
+#[derive(Serialize)]
+struct ExplainNode {
+    kind: &'static str,
+    description: String,
+    source: String,
+    span: Span,
+    children: Vec<ExplainNode>,
+}
+
+/// A plain English explanation of a regular expression, returned by
+/// `RRegex.explain`.
+///
+/// # Example
+///
+/// ```typescript
+/// import { RRegex } from "rregex"
+///
+/// const explanation = new RRegex("(?:[0-9a-f]{2}:){5}[0-9a-f]{2}").explain()
+/// expect(explanation.toText()).toBe([
+///   "in this order:",
+///   "  exactly 5 times:",
+///   "    a group:",
+///   "      in this order:",
+///   "        exactly 2 times:",
+///   '          one character from: "0" to "9" or "a" to "f":',
+///   '            "0" to "9"',
+///   '            "a" to "f"',
+///   '        the character ":"',
+///   "  exactly 2 times:",
+///   '    one character from: "0" to "9" or "a" to "f":',
+///   '      "0" to "9"',
+///   '      "a" to "f"',
+/// ].join("\n"))
+/// ```
+#[wasm_bindgen]
+pub struct RExplanation {
+    root: ExplainNode,
+}
+
+#[wasm_bindgen]
+impl RExplanation {
+    /// Returns the explanation as a tree of nodes, with the span of the
+    /// pattern each node explains.
+    ///
+    /// @returns {ExplainNode}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn tree(&self) -> Result<JsValue> {
+        serde_wasm_bindgen::to_value(&self.root)
+    }
+
+    /// Returns the explanation as text, with one line per node and the
+    /// children of a node indented below it.
+    #[wasm_bindgen(js_name = toText)]
+    pub fn to_text(&self) -> String {
+        let mut lines = Vec::new();
+        write_node(&mut lines, &self.root, 0);
+        lines.join("\n")
+    }
+}
+
+fn write_node(lines: &mut Vec<String>, node: &ExplainNode, depth: usize) {
+    let colon = if node.children.is_empty() { "" } else { ":" };
+    lines.push(format!(
+        "{}{}{}",
+        "  ".repeat(depth),
+        node.description,
+        colon
+    ));
+    for child in &node.children {
+        write_node(lines, child, depth + 1);
+    }
+}
+
+/// Explains `pattern`, parsed with the syntax options and initial flags of
+/// `options`.
+pub fn explain(
+    pattern: &str,
+    options: &RegexOptions,
+) -> std::result::Result<RExplanation, PatternError> {
+    let ast = options.ast_parser().parse(pattern)?;
+    let mut explainer = Explainer {
+        pattern,
+        flags: Flags {
+            case_insensitive: options.case_insensitive,
+            multi_line: options.multi_line,
+            dot_matches_new_line: options.dot_matches_new_line,
+            crlf: options.crlf,
+            swap_greed: options.swap_greed,
+            unicode: options.unicode,
+        },
+    };
+    let root = explainer.node(&ast);
+    Ok(RExplanation { root })
+}
+
+/// The flags in effect at some point of the pattern, they change the meaning
+/// of `.`, `^`, `$` and repetitions.
+#[derive(Clone, Copy)]
+struct Flags {
+    case_insensitive: bool,
+    multi_line: bool,
+    dot_matches_new_line: bool,
+    crlf: bool,
+    swap_greed: bool,
+    unicode: bool,
+}
+
+impl Flags {
+    fn apply(&mut self, flags: &ast::Flags) {
+        let mut enabled = true;
+        for item in &flags.items {
+            match item.kind {
+                ast::FlagsItemKind::Negation => enabled = false,
+                ast::FlagsItemKind::Flag(flag) => match flag {
+                    ast::Flag::CaseInsensitive => self.case_insensitive = enabled,
+                    ast::Flag::MultiLine => self.multi_line = enabled,
+                    ast::Flag::DotMatchesNewLine => self.dot_matches_new_line = enabled,
+                    ast::Flag::CRLF => self.crlf = enabled,
+                    ast::Flag::SwapGreed => self.swap_greed = enabled,
+                    ast::Flag::Unicode => self.unicode = enabled,
+                    ast::Flag::IgnoreWhitespace => {}
+                },
+            }
+        }
+    }
+}
+
+struct Explainer<'p> {
+    pattern: &'p str,
+    flags: Flags,
+}
+
+impl<'p> Explainer<'p> {
+    fn leaf(&self, kind: &'static str, description: String, span: &ast::Span) -> ExplainNode {
+        self.parent(kind, description, span, Vec::new())
+    }
+
+    fn parent(
+        &self,
+        kind: &'static str,
+        description: String,
+        span: &ast::Span,
+        children: Vec<ExplainNode>,
+    ) -> ExplainNode {
+        ExplainNode {
+            kind,
+            description,
+            source: self.pattern[span.start.offset..span.end.offset].to_owned(),
+            span: Span::from(span),
+            children,
+        }
+    }
+
+    fn node(&mut self, ast: &Ast) -> ExplainNode {
+        match ast {
+            Ast::Empty(span) => self.leaf("empty", "nothing (the empty string)".into(), span),
+            Ast::Flags(set) => {
+                self.flags.apply(&set.flags);
+                let description = format!("from here on: {}", flags_phrase(&set.flags));
+                self.leaf("flags", description, &set.span)
+            }
+            Ast::Literal(literal) => {
+                let text = literal.c.to_string();
+                self.leaf("literal", self.literal_description(&text), &literal.span)
+            }
+            Ast::Dot(span) => {
+                let description = if self.flags.dot_matches_new_line {
+                    "any character"
+                } else if self.flags.crlf {
+                    "any character except a newline or a carriage return"
+                } else {
+                    "any character except a newline"
+                };
+                self.leaf("dot", description.into(), span)
+            }
+            Ast::Assertion(assertion) => {
+                let description = assertion_description(&assertion.kind, self.flags.multi_line);
+                self.leaf("assertion", description.into(), &assertion.span)
+            }
+            Ast::ClassUnicode(class) => {
+                let description = unicode_phrase(class);
+                self.leaf("class", description, &class.span)
+            }
+            Ast::ClassPerl(class) => {
+                let description = perl_phrase(class, self.flags.unicode);
+                self.leaf("class", description, &class.span)
+            }
+            Ast::ClassBracketed(class) => self.bracketed(class),
+            Ast::Repetition(repetition) => {
+                let child = self.node(&repetition.ast);
+                let description = self.repetition_description(repetition);
+                self.parent("repetition", description, &repetition.span, vec![child])
+            }
+            Ast::Group(group) => {
+                let saved = self.flags;
+                let description = match &group.kind {
+                    ast::GroupKind::CaptureIndex(index) => format!("capture group {}", index),
+                    ast::GroupKind::CaptureName { name, .. } => {
+                        format!("capture group {} named {}", name.index, quote(&name.name))
+                    }
+                    ast::GroupKind::NonCapturing(flags) if flags.items.is_empty() => {
+                        "a group".into()
+                    }
+                    ast::GroupKind::NonCapturing(flags) => {
+                        self.flags.apply(flags);
+                        format!("a group with {}", flags_phrase(flags))
+                    }
+                };
+                let child = self.node(&group.ast);
+                // flags set inside of a group end with it
+                self.flags = saved;
+                self.parent("group", description, &group.span, vec![child])
+            }
+            Ast::Alternation(alternation) => {
+                let children: Vec<_> = alternation.asts.iter().map(|ast| self.node(ast)).collect();
+                let description = format!("one of these {} alternatives", children.len());
+                self.parent("alternation", description, &alternation.span, children)
+            }
+            Ast::Concat(concat) => {
+                let children = self.concat(&concat.asts);
+                if children.len() == 1 {
+                    return children.into_iter().next().unwrap();
+                }
+                self.parent("concat", "in this order".into(), &concat.span, children)
+            }
+        }
+    }
+
+    /// Explains the items of a concatenation, where consecutive literals are
+    /// explained together as a text.
+    fn concat(&mut self, asts: &[Ast]) -> Vec<ExplainNode> {
+        let mut children = Vec::new();
+        let mut index = 0;
+        while index < asts.len() {
+            let mut text = String::new();
+            let mut span = *asts[index].span();
+            while let Some(Ast::Literal(literal)) = asts.get(index) {
+                text.push(literal.c);
+                span.end = literal.span.end;
+                index += 1;
+            }
+            if text.is_empty() {
+                children.push(self.node(&asts[index]));
+                index += 1;
+            } else {
+                children.push(self.leaf("literal", self.literal_description(&text), &span));
+            }
+        }
+        children
+    }
+
+    fn literal_description(&self, text: &str) -> String {
+        let mut chars = text.chars();
+        let mut description = match (chars.next(), chars.next()) {
+            (Some(c), None) => match char_name(c) {
+                Some(name) => format!("a {}", name),
+                None => format!("the character {}", char_term(c)),
+            },
+            _ => format!("the text {}", quote(text)),
+        };
+        if self.flags.case_insensitive && text.chars().any(has_case) {
+            description.push_str(" (ignoring case)");
+        }
+        description
+    }
+
+    fn bracketed(&mut self, class: &ast::ClassBracketed) -> ExplainNode {
+        let (phrase, children) = self.class_set(&class.kind);
+        let description = if class.negated {
+            format!("any character except: {}", phrase)
+        } else {
+            format!("one character from: {}", phrase)
+        };
+        let description = if self.flags.case_insensitive && set_has_case(&class.kind) {
+            format!("{} (ignoring case)", description)
+        } else {
+            description
+        };
+        self.parent("class", description, &class.span, children)
+    }
+
+    /// Returns a phrase listing the characters of a class set, along with a
+    /// node for each of its items.
+    fn class_set(&mut self, set: &ast::ClassSet) -> (String, Vec<ExplainNode>) {
+        match set {
+            ast::ClassSet::Item(ast::ClassSetItem::Union(union)) => {
+                let mut phrases = Vec::new();
+                let mut children = Vec::new();
+                for item in &union.items {
+                    let (phrase, node) = self.class_item(item);
+                    phrases.push(phrase);
+                    children.push(node);
+                }
+                (join_or(&phrases), children)
+            }
+            ast::ClassSet::Item(item) => {
+                let (phrase, node) = self.class_item(item);
+                (phrase, vec![node])
+            }
+            ast::ClassSet::BinaryOp(op) => {
+                let (lhs, _) = self.class_set(&op.lhs);
+                let (rhs, _) = self.class_set(&op.rhs);
+                let phrase = match op.kind {
+                    ast::ClassSetBinaryOpKind::Intersection => {
+                        format!("both ({}) and ({})", lhs, rhs)
+                    }
+                    ast::ClassSetBinaryOpKind::Difference => {
+                        format!("({}) but not ({})", lhs, rhs)
+                    }
+                    ast::ClassSetBinaryOpKind::SymmetricDifference => {
+                        format!("either ({}) or ({}) but not both", lhs, rhs)
+                    }
+                };
+                let children = vec![self.class_set_node(&op.lhs), self.class_set_node(&op.rhs)];
+                (phrase, children)
+            }
+        }
+    }
+
+    fn class_set_node(&mut self, set: &ast::ClassSet) -> ExplainNode {
+        if let ast::ClassSet::Item(item) = set {
+            if !matches!(item, ast::ClassSetItem::Union(_)) {
+                return self.class_item(item).1;
+            }
+        }
+        let (phrase, children) = self.class_set(set);
+        let description = format!("one character from: {}", phrase);
+        self.parent("class", description, set.span(), children)
+    }
+
+    fn class_item(&mut self, item: &ast::ClassSetItem) -> (String, ExplainNode) {
+        let (kind, phrase) = match item {
+            ast::ClassSetItem::Empty(_) => ("empty", "nothing".to_owned()),
+            ast::ClassSetItem::Literal(literal) => ("literal", char_term(literal.c)),
+            ast::ClassSetItem::Range(range) => (
+                "range",
+                format!("{} to {}", char_term(range.start.c), char_term(range.end.c)),
+            ),
+            ast::ClassSetItem::Ascii(class) => ("class", ascii_phrase(class)),
+            ast::ClassSetItem::Unicode(class) => ("class", unicode_phrase(class)),
+            ast::ClassSetItem::Perl(class) => ("class", perl_phrase(class, self.flags.unicode)),
+            ast::ClassSetItem::Bracketed(class) => {
+                let node = self.bracketed(class);
+                let (phrase, _) = self.class_set(&class.kind);
+                let phrase = if class.negated {
+                    format!("a character that is not {}", phrase)
+                } else {
+                    phrase
+                };
+                return (phrase, node);
+            }
+            ast::ClassSetItem::Union(_) => {
+                let (phrase, children) = self.class_set(&ast::ClassSet::Item(item.clone()));
+                let node = self.parent("class", phrase.clone(), item.span(), children);
+                return (phrase, node);
+            }
+        };
+        (phrase.clone(), self.leaf(kind, phrase, item.span()))
+    }
+
+    fn repetition_description(&self, repetition: &ast::Repetition) -> String {
+        let mut description = match &repetition.op.kind {
+            ast::RepetitionKind::ZeroOrOne => "optionally".to_owned(),
+            ast::RepetitionKind::ZeroOrMore => "zero or more times".to_owned(),
+            ast::RepetitionKind::OneOrMore => "one or more times".to_owned(),
+            ast::RepetitionKind::Range(ast::RepetitionRange::Exactly(1)) => {
+                "exactly once".to_owned()
+            }
+            ast::RepetitionKind::Range(ast::RepetitionRange::Exactly(n)) => {
+                format!("exactly {} times", n)
+            }
+            ast::RepetitionKind::Range(ast::RepetitionRange::AtLeast(n)) => {
+                format!("at least {} times", n)
+            }
+            ast::RepetitionKind::Range(ast::RepetitionRange::Bounded(min, max)) => {
+                format!("between {} and {} times", min, max)
+            }
+        };
+        if repetition.greedy == self.flags.swap_greed {
+            description.push_str(", as few as possible");
+        }
+        description
+    }
+}
+
+fn assertion_description(kind: &ast::AssertionKind, multi_line: bool) -> &'static str {
+    match kind {
+        ast::AssertionKind::StartLine if multi_line => "the start of a line",
+        ast::AssertionKind::EndLine if multi_line => "the end of a line",
+        ast::AssertionKind::StartLine | ast::AssertionKind::StartText => "the start of the text",
+        ast::AssertionKind::EndLine | ast::AssertionKind::EndText => "the end of the text",
+        ast::AssertionKind::WordBoundary => "a word boundary",
+        ast::AssertionKind::NotWordBoundary => "a position that is not a word boundary",
+        ast::AssertionKind::WordBoundaryStart | ast::AssertionKind::WordBoundaryStartAngle => {
+            "the start of a word"
+        }
+        ast::AssertionKind::WordBoundaryEnd | ast::AssertionKind::WordBoundaryEndAngle => {
+            "the end of a word"
+        }
+        ast::AssertionKind::WordBoundaryStartHalf => "a position not preceded by a word character",
+        ast::AssertionKind::WordBoundaryEndHalf => "a position not followed by a word character",
+    }
+}
+
+fn flags_phrase(flags: &ast::Flags) -> String {
+    let mut enabled = true;
+    let mut phrases = Vec::new();
+    for item in &flags.items {
+        let name = match item.kind {
+            ast::FlagsItemKind::Negation => {
+                enabled = false;
+                continue;
+            }
+            ast::FlagsItemKind::Flag(ast::Flag::CaseInsensitive) => "case insensitive matching",
+            ast::FlagsItemKind::Flag(ast::Flag::MultiLine) => "multi-line mode",
+            ast::FlagsItemKind::Flag(ast::Flag::DotMatchesNewLine) => "dot matches newline",
+            ast::FlagsItemKind::Flag(ast::Flag::CRLF) => "CRLF mode",
+            ast::FlagsItemKind::Flag(ast::Flag::SwapGreed) => "swapped greediness",
+            ast::FlagsItemKind::Flag(ast::Flag::Unicode) => "Unicode mode",
+            ast::FlagsItemKind::Flag(ast::Flag::IgnoreWhitespace) => "verbose mode",
+        };
+        phrases.push(format!("{} {}", name, if enabled { "on" } else { "off" }));
+    }
+    phrases.join(", ")
+}
+
+fn perl_phrase(class: &ast::ClassPerl, unicode: bool) -> String {
+    let phrase = match (&class.kind, unicode) {
+        (ast::ClassPerlKind::Digit, true) => "a digit",
+        (ast::ClassPerlKind::Digit, false) => "an ASCII digit",
+        (ast::ClassPerlKind::Space, true) => "a whitespace character",
+        (ast::ClassPerlKind::Space, false) => "an ASCII whitespace character",
+        (ast::ClassPerlKind::Word, true) => "a word character (letter, digit or underscore)",
+        (ast::ClassPerlKind::Word, false) => {
+            "an ASCII word character (letter, digit or underscore)"
+        }
+    };
+    negate(phrase, class.negated)
+}
+
+fn ascii_phrase(class: &ast::ClassAscii) -> String {
+    let phrase = match class.kind {
+        ast::ClassAsciiKind::Alnum => "an ASCII letter or digit",
+        ast::ClassAsciiKind::Alpha => "an ASCII letter",
+        ast::ClassAsciiKind::Ascii => "an ASCII character",
+        ast::ClassAsciiKind::Blank => "a space or a tab",
+        ast::ClassAsciiKind::Cntrl => "an ASCII control character",
+        ast::ClassAsciiKind::Digit => "an ASCII digit",
+        ast::ClassAsciiKind::Graph => "a visible ASCII character",
+        ast::ClassAsciiKind::Lower => "a lowercase ASCII letter",
+        ast::ClassAsciiKind::Print => "a printable ASCII character",
+        ast::ClassAsciiKind::Punct => "an ASCII punctuation character",
+        ast::ClassAsciiKind::Space => "an ASCII whitespace character",
+        ast::ClassAsciiKind::Upper => "an uppercase ASCII letter",
+        ast::ClassAsciiKind::Word => "an ASCII word character (letter, digit or underscore)",
+        ast::ClassAsciiKind::Xdigit => "a hexadecimal digit",
+    };
+    negate(phrase, class.negated)
+}
+
+fn unicode_phrase(class: &ast::ClassUnicode) -> String {
+    let phrase = match &class.kind {
+        ast::ClassUnicodeKind::OneLetter(letter) => property_phrase(&letter.to_string()),
+        ast::ClassUnicodeKind::Named(name) => property_phrase(name),
+        ast::ClassUnicodeKind::NamedValue { name, value, .. } => {
+            return format!(
+                "a character whose Unicode property {} is {}{}",
+                quote(name),
+                if class.is_negated() { "not " } else { "" },
+                quote(value)
+            );
+        }
+    };
+    negate(&phrase, class.is_negated())
+}
+
+/// Describes the common Unicode general categories and binary properties,
+/// other names (e.g. scripts) are quoted as they are.
+fn property_phrase(name: &str) -> String {
+    let normalized: String = name
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect();
+    let phrase = match normalized.as_str() {
+        "l" | "letter" => "a letter",
+        "lu" | "uppercaseletter" => "an uppercase letter",
+        "ll" | "lowercaseletter" => "a lowercase letter",
+        "m" | "mark" | "combiningmark" => "a combining mark",
+        "n" | "number" => "a number",
+        "nd" | "decimalnumber" | "digit" => "a decimal digit",
+        "p" | "punctuation" | "punct" => "a punctuation character",
+        "s" | "symbol" => "a symbol",
+        "z" | "separator" => "a separator",
+        "zs" | "spaceseparator" => "a space separator",
+        "cc" | "control" | "cntrl" => "a control character",
+        "whitespace" | "wspace" | "space" => "a whitespace character",
+        "alphabetic" | "alpha" => "an alphabetic character",
+        "uppercase" | "upper" => "an uppercase character",
+        "lowercase" | "lower" => "a lowercase character",
+        "emoji" => "an emoji",
+        _ => return format!("a character with the Unicode property {}", quote(name)),
+    };
+    phrase.to_owned()
+}
+
+fn negate(phrase: &str, negated: bool) -> String {
+    if negated {
+        format!("a character that is not {}", phrase)
+    } else {
+        phrase.to_owned()
+    }
+}
+
+/// Returns the name of a character that is hard to read when quoted.
+fn char_name(c: char) -> Option<&'static str> {
+    match c {
+        '\n' => Some("newline"),
+        '\r' => Some("carriage return"),
+        '\t' => Some("tab"),
+        ' ' => Some("space"),
+        '\x0B' => Some("vertical tab"),
+        '\x0C' => Some("form feed"),
+        '\x07' => Some("bell"),
+        '\0' => Some("null character"),
+        _ => None,
+    }
+}
+
+/// Returns a character as it's written in a list, e.g. `"a"`, `newline` or
+/// `U+200B`.
+fn char_term(c: char) -> String {
+    match char_name(c) {
+        Some(name) => name.to_owned(),
+        None if c.is_control() || (c.is_whitespace() && c != ' ') || is_invisible(c) => {
+            format!("U+{:04X}", c as u32)
+        }
+        None => quote(&c.to_string()),
+    }
+}
+
+fn is_invisible(c: char) -> bool {
+    matches!(c, '\u{200B}'..='\u{200F}' | '\u{2060}' | '\u{FEFF}')
+}
+
+/// Whether a class set has characters that case insensitive matching
+/// changes, so `[a-z]` does but `[0-9]` doesn't.
+fn set_has_case(set: &ast::ClassSet) -> bool {
+    match set {
+        ast::ClassSet::Item(item) => item_has_case(item),
+        ast::ClassSet::BinaryOp(op) => set_has_case(&op.lhs) || set_has_case(&op.rhs),
+    }
+}
+
+fn item_has_case(item: &ast::ClassSetItem) -> bool {
+    match item {
+        ast::ClassSetItem::Literal(literal) => has_case(literal.c),
+        ast::ClassSetItem::Range(range) => has_case(range.start.c) || has_case(range.end.c),
+        ast::ClassSetItem::Bracketed(class) => set_has_case(&class.kind),
+        ast::ClassSetItem::Union(union) => union.items.iter().any(item_has_case),
+        _ => false,
+    }
+}
+
+fn has_case(c: char) -> bool {
+    c.to_lowercase().ne(c.to_uppercase())
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.escape_debug())
+}
+
+/// Joins phrases like `a, b or c`.
+fn join_or(phrases: &[String]) -> String {
+    match phrases.split_last() {
+        None => String::new(),
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+    }
+}
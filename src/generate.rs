@@ -0,0 +1,327 @@
+use regex_automata::meta;
+use regex_automata::util::primitives::StateID;
+use regex_automata::{Anchored, Input};
+use regex_syntax::hir::{Class, ClassUnicodeRange, Hir, HirKind, Look};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use wasm_bindgen::prelude::*;
+
+use crate::dfa;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+#[wasm_bindgen(typescript_custom_section)]
+const GENERATE_OPTIONS_TYPE: &'static str = r#"/**
+ * Options accepted by `RRegex.generate`.
+ */
+export type GenerateOptions = {
+  /** The number of strings to generate. Defaults to `10`. */
+  count?: number
+
+  /**
+   * The seed of the random generator, the same seed always generates the
+   * same strings. Defaults to `0`.
+   */
+  seed?: number
+
+  /**
+   * The maximum number of times a repetition is repeated beyond its
+   * minimum, e.g. `a*` generates at most 8 `a` and `a{2,}` at most 10 by
+   * default. Defaults to `8`.
+   */
+  maxRepeat?: number
+}
+
+/**
+ * Options accepted by `RRegex.enumerate`.
+ */
+export type EnumerateOptions = {
+  /** The maximum number of strings to list. Defaults to `100`. */
+  limit?: number
+}"#;
+
+#[derive(Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GenerateOptions {
+    count: usize,
+    seed: u64,
+    max_repeat: u32,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        GenerateOptions {
+            count: 10,
+            seed: 0,
+            max_repeat: 8,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct EnumerateOptions {
+    limit: usize,
+}
+
+impl Default for EnumerateOptions {
+    fn default() -> Self {
+        EnumerateOptions { limit: 100 }
+    }
+}
+
+/// The number of attempts to generate each string when the pattern has
+/// assertions, since the generated strings may not satisfy them.
+const ATTEMPTS: usize = 100;
+
+/// A small and fast pseudo random generator (SplitMix64), good enough to
+/// generate test data and stable across platforms.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..bound`.
+    fn below(&mut self, bound: u64) -> u64 {
+        ((self.next() as u128 * bound as u128) >> 64) as u64
+    }
+}
+
+/// Generates random strings matched by `hir` as a whole.
+///
+/// Assertions are not taken into account while generating, so when `hir`
+/// has any, every string is checked and generated again if it doesn't
+/// match. Patterns that can't match anything, or whose assertions are
+/// rarely satisfied, may generate fewer strings than requested.
+pub fn generate(hir: &Hir, options: &GenerateOptions) -> Result<Vec<String>> {
+    let verifier = if hir.properties().look_set().is_empty() {
+        None
+    } else {
+        let whole = Hir::concat(vec![hir.clone(), Hir::look(Look::End)]);
+        let regex = meta::Regex::builder()
+            .build_from_hir(&whole)
+            .map_err(serde_wasm_bindgen::Error::new)?;
+        Some(regex)
+    };
+
+    let mut rng = Rng(options.seed);
+    // `count` comes from the caller, so the strings are only allocated as
+    // they are generated
+    let mut strings = Vec::new();
+    let mut bytes = Vec::new();
+    for _ in 0..options.count {
+        for _ in 0..ATTEMPTS {
+            bytes.clear();
+            if !generate_into(&mut bytes, hir, &mut rng, options.max_repeat) {
+                continue;
+            }
+            let text = String::from_utf8_lossy(&bytes);
+            let matched = verifier.as_ref().is_none_or(|regex| {
+                regex.is_match(Input::new(text.as_ref()).anchored(Anchored::Yes))
+            });
+            if matched {
+                strings.push(text.into_owned());
+                break;
+            }
+        }
+    }
+    Ok(strings)
+}
+
+/// The first and last surrogate code points, which aren't chars.
+const SURROGATES: (u32, u32) = (0xD800, 0xDFFF);
+
+/// Returns the number of chars in `range`, without the surrogates it spans.
+fn scalar_count(range: &ClassUnicodeRange) -> u64 {
+    let len = range.len() as u64;
+    if u32::from(range.start()) < SURROGATES.0 && u32::from(range.end()) > SURROGATES.1 {
+        len - u64::from(SURROGATES.1 - SURROGATES.0 + 1)
+    } else {
+        len
+    }
+}
+
+/// Appends a random string matched by `hir` to `out`, returns `false` if
+/// `hir` can't match anything (e.g. an empty class).
+fn generate_into(out: &mut Vec<u8>, hir: &Hir, rng: &mut Rng, max_repeat: u32) -> bool {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => true,
+        HirKind::Literal(literal) => {
+            out.extend_from_slice(&literal.0);
+            true
+        }
+        HirKind::Class(Class::Unicode(class)) => {
+            let total: u64 = class.ranges().iter().map(scalar_count).sum();
+            if total == 0 {
+                return false;
+            }
+            let mut index = rng.below(total);
+            for range in class.ranges() {
+                let len = scalar_count(range);
+                if index < len {
+                    // the bounds of a range are chars, but a range can
+                    // span the surrogates, which are skipped
+                    let mut c = u32::from(range.start()) + index as u32;
+                    if u32::from(range.start()) < SURROGATES.0 && c >= SURROGATES.0 {
+                        c += SURROGATES.1 - SURROGATES.0 + 1;
+                    }
+                    let c = char::from_u32(c).expect("a scalar value");
+                    out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                    break;
+                }
+                index -= len;
+            }
+            true
+        }
+        HirKind::Class(Class::Bytes(class)) => {
+            let total: u64 = class.ranges().iter().map(|range| range.len() as u64).sum();
+            if total == 0 {
+                return false;
+            }
+            let mut index = rng.below(total);
+            for range in class.ranges() {
+                let len = range.len() as u64;
+                if index < len {
+                    out.push(range.start() + index as u8);
+                    break;
+                }
+                index -= len;
+            }
+            true
+        }
+        HirKind::Repetition(repetition) => {
+            let max = repetition
+                .max
+                .unwrap_or(u32::MAX)
+                .min(repetition.min.saturating_add(max_repeat));
+            let count = repetition.min + rng.below(u64::from(max - repetition.min) + 1) as u32;
+            (0..count).all(|_| generate_into(out, &repetition.sub, rng, max_repeat))
+        }
+        HirKind::Capture(capture) => generate_into(out, &capture.sub, rng, max_repeat),
+        HirKind::Concat(items) => items
+            .iter()
+            .all(|item| generate_into(out, item, rng, max_repeat)),
+        HirKind::Alternation(alternatives) => {
+            let index = rng.below(alternatives.len() as u64) as usize;
+            generate_into(out, &alternatives[index], rng, max_repeat)
+        }
+    }
+}
+
+/// Lists up to `options.limit` strings matched by `hir` as a whole, in
+/// shortlex order: shorter strings (in characters) first, and strings of
+/// the same length in the order of their code points.
+pub fn enumerate(
+    hir: &Hir,
+    options: &EnumerateOptions,
+    size_limit: Option<usize>,
+) -> Result<Vec<String>> {
+    let dfa = dfa::whole_match(hir, size_limit)?;
+    let start = dfa::start(&dfa);
+
+    // the states reachable from the start, and the ones that can still
+    // reach an accepting state
+    let mut successors: HashMap<StateID, Vec<StateID>> = HashMap::new();
+    let mut queue = VecDeque::from(vec![start]);
+    while let Some(state) = queue.pop_front() {
+        if successors.contains_key(&state) {
+            continue;
+        }
+        let next = dfa::successors(&dfa, state);
+        queue.extend(next.iter().copied());
+        successors.insert(state, next);
+    }
+    let mut live: HashSet<StateID> = successors
+        .keys()
+        .copied()
+        .filter(|state| dfa::is_accepting(&dfa, *state))
+        .collect();
+    loop {
+        let before = live.len();
+        for (state, next) in &successors {
+            if next.iter().any(|next| live.contains(next)) {
+                live.insert(*state);
+            }
+        }
+        if live.len() == before {
+            break;
+        }
+    }
+
+    let mut enumerator = Enumerator {
+        dfa: &dfa,
+        limit: options.limit,
+        strings: Vec::new(),
+        buf: Vec::new(),
+    };
+    // `accepts[k]` are the states that accept a string of `k` more characters
+    let mut accepts: Vec<HashSet<StateID>> = vec![successors
+        .keys()
+        .copied()
+        .filter(|state| dfa::is_accepting(&dfa, *state))
+        .collect()];
+    let mut front: HashSet<StateID> = Some(start)
+        .filter(|start| live.contains(start))
+        .into_iter()
+        .collect();
+    let mut length = 0;
+    while !front.is_empty() && enumerator.strings.len() < enumerator.limit {
+        while accepts.len() <= length {
+            let previous = accepts.last().unwrap();
+            let next = successors
+                .iter()
+                .filter(|(_, next)| next.iter().any(|next| previous.contains(next)))
+                .map(|(state, _)| *state)
+                .collect();
+            accepts.push(next);
+        }
+        if accepts[length].contains(&start) {
+            enumerator.strings_of_length(&accepts, start, length);
+        }
+        front = front
+            .iter()
+            .flat_map(|state| successors[state].iter().copied())
+            .filter(|state| live.contains(state))
+            .collect();
+        length += 1;
+    }
+    Ok(enumerator.strings)
+}
+
+struct Enumerator<'d> {
+    dfa: &'d dfa::Dfa,
+    limit: usize,
+    strings: Vec<String>,
+    buf: Vec<u8>,
+}
+
+impl<'d> Enumerator<'d> {
+    /// Adds the strings of `remaining` more characters accepted from
+    /// `state`, in order, returns `false` once the limit is reached.
+    fn strings_of_length(
+        &mut self,
+        accepts: &[HashSet<StateID>],
+        state: StateID,
+        remaining: usize,
+    ) -> bool {
+        if remaining == 0 {
+            self.strings
+                .push(String::from_utf8_lossy(&self.buf).into_owned());
+            return self.strings.len() < self.limit;
+        }
+        let dfa = self.dfa;
+        let keep = |next| accepts[remaining - 1].contains(&next);
+        dfa::each_char(dfa, state, keep, &mut |bytes, next| {
+            self.buf.extend_from_slice(bytes);
+            let more = self.strings_of_length(accepts, next, remaining - 1);
+            self.buf.truncate(self.buf.len() - bytes.len());
+            more
+        })
+    }
+}
@@ -0,0 +1,324 @@
+use serde::Deserialize;
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+
+use crate::error::PatternError;
+
+#[wasm_bindgen(typescript_custom_section)]
+const GLOB_OPTIONS_TYPE: &'static str = r#"/**
+ * Options accepted by `RGlob` and `RGlobSet`.
+ *
+ * @see https://docs.rs/globset/latest/globset/struct.GlobBuilder.html
+ */
+export type GlobOptions = {
+  /**
+   * Whether `*` and `?` must not match the path separator `/`. When `false`,
+   * `*.rs` also matches `src/lib.rs`. Defaults to `false`.
+   */
+  literalSeparator?: boolean
+
+  /** Whether to match paths case insensitively. Defaults to `false`. */
+  caseInsensitive?: boolean
+
+  /**
+   * Whether `\` escapes the special meaning of the next character, like in
+   * `\*.rs`. When `false`, `\` is a literal character. Defaults to `true`.
+   */
+  backslashEscape?: boolean
+}"#;
+
+#[derive(Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GlobOptions {
+    pub literal_separator: bool,
+    pub case_insensitive: bool,
+    pub backslash_escape: bool,
+}
+
+impl Default for GlobOptions {
+    fn default() -> Self {
+        GlobOptions {
+            literal_separator: false,
+            case_insensitive: false,
+            backslash_escape: true,
+        }
+    }
+}
+
+enum Token {
+    Literal(char),
+    /// `?`
+    Any,
+    /// `*`
+    ZeroOrMore,
+    /// `**/` at the start of the glob.
+    RecursivePrefix,
+    /// `/**` at the end of the glob.
+    RecursiveSuffix,
+    /// `/**/` anywhere in the glob.
+    RecursiveZeroOrMore,
+    /// `**` as the whole glob.
+    RecursiveAll,
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+    Alternates(Vec<Vec<Token>>),
+}
+
+/// Translates a shell glob into a regular expression that matches a whole
+/// path. The flags are set in the regular expression itself, so it can be
+/// compiled with the default options.
+pub fn to_regex(glob: &str, options: &GlobOptions) -> Result<String, PatternError> {
+    let mut parser = GlobParser {
+        glob,
+        chars: glob.char_indices().collect(),
+        index: 0,
+        options,
+    };
+    let tokens = parser.sequence(0)?;
+
+    let mut regex = String::from(if options.case_insensitive {
+        "(?is)^"
+    } else {
+        "(?s)^"
+    });
+    write_tokens(&mut regex, &tokens, options);
+    regex.push('$');
+    Ok(regex)
+}
+
+struct GlobParser<'g> {
+    glob: &'g str,
+    chars: Vec<(usize, char)>,
+    index: usize,
+    options: &'g GlobOptions,
+}
+
+impl<'g> GlobParser<'g> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).map(|(_, c)| *c)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.index += 1;
+        }
+        c
+    }
+
+    fn offset_of(&self, index: usize) -> usize {
+        self.chars
+            .get(index)
+            .map(|(offset, _)| *offset)
+            .unwrap_or(self.glob.len())
+    }
+
+    fn error<M: ToString>(&self, message: M, start: usize) -> PatternError {
+        PatternError::syntax(
+            message,
+            self.glob,
+            self.offset_of(start),
+            self.offset_of(self.index),
+        )
+    }
+
+    /// Parses tokens up to the end of the glob, or up to the `,` or `}` that
+    /// ends an alternative when `depth` is greater than zero.
+    fn sequence(&mut self, depth: usize) -> Result<Vec<Token>, PatternError> {
+        let mut tokens = Vec::new();
+        while let Some(c) = self.peek() {
+            let start = self.index;
+            match c {
+                ',' | '}' if depth > 0 => break,
+                '}' => {
+                    self.bump();
+                    return Err(self.error("unopened alternate group", start));
+                }
+                '?' => {
+                    self.bump();
+                    tokens.push(Token::Any);
+                }
+                '*' => {
+                    self.bump();
+                    self.stars(&mut tokens, depth);
+                }
+                '[' => {
+                    self.bump();
+                    tokens.push(self.class(start)?);
+                }
+                '{' => {
+                    self.bump();
+                    let mut alternates = vec![self.sequence(depth + 1)?];
+                    loop {
+                        match self.bump() {
+                            Some(',') => alternates.push(self.sequence(depth + 1)?),
+                            Some('}') => break,
+                            _ => return Err(self.error("unclosed alternate group", start)),
+                        }
+                    }
+                    tokens.push(Token::Alternates(alternates));
+                }
+                '\\' if self.options.backslash_escape => {
+                    self.bump();
+                    match self.bump() {
+                        Some(c) => tokens.push(Token::Literal(c)),
+                        None => return Err(self.error("dangling escape", start)),
+                    }
+                }
+                c => {
+                    self.bump();
+                    tokens.push(Token::Literal(c));
+                }
+            }
+        }
+        Ok(tokens)
+    }
+
+    /// Parses `*` or `**`, after the first `*`.
+    ///
+    /// `**` is only recursive when it is a whole path component, otherwise it
+    /// behaves like `*`.
+    fn stars(&mut self, tokens: &mut Vec<Token>, depth: usize) {
+        if self.peek() != Some('*') {
+            tokens.push(Token::ZeroOrMore);
+            return;
+        }
+        self.bump();
+
+        let after_separator = matches!(tokens.last(), Some(Token::Literal('/')));
+        let at_start = tokens.is_empty();
+        let before_separator = self.peek() == Some('/');
+        let at_end = match self.peek() {
+            None => true,
+            Some(',') | Some('}') => depth > 0,
+            _ => false,
+        };
+
+        if at_start && before_separator {
+            self.bump();
+            tokens.push(Token::RecursivePrefix);
+        } else if after_separator && before_separator {
+            self.bump();
+            tokens.pop();
+            tokens.push(Token::RecursiveZeroOrMore);
+        } else if after_separator && at_end {
+            tokens.pop();
+            tokens.push(Token::RecursiveSuffix);
+        } else if at_start && at_end {
+            // `**` alone matches every path, even with a literal separator
+            tokens.push(Token::RecursiveAll);
+        } else {
+            tokens.push(Token::ZeroOrMore);
+        }
+    }
+
+    /// Parses a character class, after its `[`.
+    fn class(&mut self, start: usize) -> Result<Token, PatternError> {
+        let negated = matches!(self.peek(), Some('!') | Some('^'));
+        if negated {
+            self.bump();
+        }
+
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let c = match self.bump() {
+                None => return Err(self.error("unclosed character class", start)),
+                Some(']') if !first => break,
+                Some('\\') if self.options.backslash_escape => match self.bump() {
+                    Some(c) => c,
+                    None => return Err(self.error("unclosed character class", start)),
+                },
+                Some(c) => c,
+            };
+            first = false;
+
+            let range_start = self.index - 1;
+            let is_range = self.peek() == Some('-')
+                && !matches!(self.chars.get(self.index + 1), None | Some((_, ']')));
+            if !is_range {
+                ranges.push((c, c));
+                continue;
+            }
+
+            self.bump();
+            let end = match self.bump() {
+                Some('\\') if self.options.backslash_escape => match self.bump() {
+                    Some(end) => end,
+                    None => return Err(self.error("unclosed character class", start)),
+                },
+                Some(end) => end,
+                None => return Err(self.error("unclosed character class", start)),
+            };
+            if end < c {
+                return Err(self.error(
+                    format!("invalid range {}-{} in character class", c, end),
+                    range_start,
+                ));
+            }
+            ranges.push((c, end));
+        }
+
+        Ok(Token::Class { negated, ranges })
+    }
+}
+
+fn write_tokens(out: &mut String, tokens: &[Token], options: &GlobOptions) {
+    let any = if options.literal_separator {
+        "[^/]"
+    } else {
+        "."
+    };
+    for token in tokens {
+        match token {
+            Token::Literal(c) => write_char(out, *c),
+            Token::Any => out.push_str(any),
+            Token::ZeroOrMore => {
+                out.push_str(any);
+                out.push('*');
+            }
+            Token::RecursivePrefix => out.push_str("(?:/?|.*/)"),
+            Token::RecursiveSuffix => out.push_str("/.*"),
+            Token::RecursiveZeroOrMore => out.push_str("(?:/|/.*/)"),
+            Token::RecursiveAll => out.push_str(".*"),
+            Token::Class { negated, ranges } => {
+                out.push_str(if *negated { "[^" } else { "[" });
+                for (start, end) in ranges {
+                    write_char(out, *start);
+                    if start != end {
+                        out.push('-');
+                        write_char(out, *end);
+                    }
+                }
+                // a negated class must not match the separator either
+                if *negated && options.literal_separator {
+                    out.push('/');
+                }
+                out.push(']');
+            }
+            Token::Alternates(alternates) => {
+                out.push_str("(?:");
+                for (i, alternate) in alternates.iter().enumerate() {
+                    if i > 0 {
+                        out.push('|');
+                    }
+                    write_tokens(out, alternate, options);
+                }
+                out.push(')');
+            }
+        }
+    }
+}
+
+fn write_char(out: &mut String, c: char) {
+    if regex_syntax::is_meta_character(c) {
+        out.push('\\');
+        out.push(c);
+    } else if c.is_control() {
+        let _ = write!(out, "\\x{{{:X}}}", c as u32);
+    } else {
+        out.push(c);
+    }
+}
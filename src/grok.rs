@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+
+use crate::error::PatternError;
+
+/// The definitions every `RGrok` starts with, adapted from the base
+/// patterns of Logstash to the syntax of the regex crate, which has no
+/// look-around, so boundaries use `\b` instead.
+pub const BASE: &[(&str, &str)] = &[
+    ("USERNAME", r"[a-zA-Z0-9._-]+"),
+    ("USER", r"%{USERNAME}"),
+    (
+        "EMAILLOCALPART",
+        r"[a-zA-Z0-9!#$%&'*+/=?^_`{|}~-]+(?:\.[a-zA-Z0-9!#$%&'*+/=?^_`{|}~-]+)*",
+    ),
+    ("EMAILADDRESS", r"%{EMAILLOCALPART}@%{HOSTNAME}"),
+    ("INT", r"[+-]?[0-9]+"),
+    ("BASE10NUM", r"[+-]?(?:[0-9]+(?:\.[0-9]+)?|\.[0-9]+)"),
+    ("NUMBER", r"%{BASE10NUM}"),
+    ("BASE16NUM", r"[+-]?(?:0[xX])?[0-9A-Fa-f]+"),
+    ("POSINT", r"\b[1-9][0-9]*\b"),
+    ("NONNEGINT", r"\b[0-9]+\b"),
+    ("WORD", r"\b\w+\b"),
+    ("NOTSPACE", r"\S+"),
+    ("SPACE", r"\s*"),
+    ("DATA", r".*?"),
+    ("GREEDYDATA", r".*"),
+    ("QUOTEDSTRING", r#""(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'"#),
+    ("QS", r"%{QUOTEDSTRING}"),
+    (
+        "UUID",
+        r"[A-Fa-f0-9]{8}-(?:[A-Fa-f0-9]{4}-){3}[A-Fa-f0-9]{12}",
+    ),
+    ("MAC", r"(?:[A-Fa-f0-9]{2}[:-]){5}[A-Fa-f0-9]{2}"),
+    (
+        "IPV4",
+        r"\b(?:(?:25[0-5]|2[0-4][0-9]|[01]?[0-9][0-9]?)\.){3}(?:25[0-5]|2[0-4][0-9]|[01]?[0-9][0-9]?)\b",
+    ),
+    (
+        "IPV6",
+        concat!(
+            r"(?:(?:[0-9A-Fa-f]{1,4}:){7}[0-9A-Fa-f]{1,4}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){6}%{IPV4}",
+            r"|::(?:[fF]{4}(?::0{1,4})?:)?%{IPV4}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,4}:%{IPV4}",
+            r"|[0-9A-Fa-f]{1,4}:(?::[0-9A-Fa-f]{1,4}){1,6}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,2}(?::[0-9A-Fa-f]{1,4}){1,5}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,3}(?::[0-9A-Fa-f]{1,4}){1,4}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,4}(?::[0-9A-Fa-f]{1,4}){1,3}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,5}(?::[0-9A-Fa-f]{1,4}){1,2}",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,6}:[0-9A-Fa-f]{1,4}",
+            r"|:(?:(?::[0-9A-Fa-f]{1,4}){1,7}|:)",
+            r"|(?:[0-9A-Fa-f]{1,4}:){1,7}:)",
+        ),
+    ),
+    ("IP", r"%{IPV6}|%{IPV4}"),
+    (
+        "HOSTNAME",
+        r"\b[0-9A-Za-z][0-9A-Za-z-]{0,62}(?:\.[0-9A-Za-z][0-9A-Za-z-]{0,62})*\.?\b",
+    ),
+    ("IPORHOST", r"%{IP}|%{HOSTNAME}"),
+    ("HOSTPORT", r"%{IPORHOST}:%{POSINT}"),
+    ("URIPROTO", r"[A-Za-z][A-Za-z0-9+.-]*"),
+    ("URIHOST", r"%{IPORHOST}(?::%{POSINT})?"),
+    ("URIPATH", r"(?:/[A-Za-z0-9$.+!*'(),~:;=@#%&_-]*)+"),
+    ("URIQUERY", r"[A-Za-z0-9$.+!*'|(),~@#%&/=:;_?\[\]<>-]*"),
+    ("URIPARAM", r"\?%{URIQUERY}"),
+    ("URIPATHPARAM", r"%{URIPATH}(?:%{URIPARAM})?"),
+    (
+        "URI",
+        r"%{URIPROTO}://(?:%{USER}(?::[^@]*)?@)?%{URIHOST}?(?:%{URIPATHPARAM})?",
+    ),
+    ("UNIXPATH", r"(?:/[\w%!$@:.,+~-]*)+"),
+    ("WINPATH", r"(?:[A-Za-z]+:|\\)(?:\\[^\\?*]*)+"),
+    ("PATH", r"%{UNIXPATH}|%{WINPATH}"),
+    (
+        "MONTH",
+        r"\b(?:Jan(?:uary)?|Feb(?:ruary)?|Mar(?:ch)?|Apr(?:il)?|May|June?|July?|Aug(?:ust)?|Sep(?:tember)?|Oct(?:ober)?|Nov(?:ember)?|Dec(?:ember)?)\b",
+    ),
+    ("MONTHNUM", r"0?[1-9]|1[0-2]"),
+    ("MONTHDAY", r"0[1-9]|[12][0-9]|3[01]|[1-9]"),
+    (
+        "DAY",
+        r"\b(?:Mon(?:day)?|Tue(?:sday)?|Wed(?:nesday)?|Thu(?:rsday)?|Fri(?:day)?|Sat(?:urday)?|Sun(?:day)?)\b",
+    ),
+    ("YEAR", r"(?:[0-9]{2}){1,2}"),
+    ("HOUR", r"2[0-3]|[01]?[0-9]"),
+    ("MINUTE", r"[0-5][0-9]"),
+    ("SECOND", r"(?:[0-5]?[0-9]|60)(?:[:.,][0-9]+)?"),
+    ("TIME", r"%{HOUR}:%{MINUTE}(?::%{SECOND})?"),
+    ("ISO8601_TIMEZONE", r"Z|[+-]%{HOUR}(?::?%{MINUTE})"),
+    (
+        "TIMESTAMP_ISO8601",
+        r"%{YEAR}-%{MONTHNUM}-%{MONTHDAY}[T ]%{HOUR}:?%{MINUTE}(?::?%{SECOND})?%{ISO8601_TIMEZONE}?",
+    ),
+    ("HTTPDATE", r"%{MONTHDAY}/%{MONTH}/%{YEAR}:%{TIME} %{INT}"),
+    ("SYSLOGTIMESTAMP", r"%{MONTH} +%{MONTHDAY} %{TIME}"),
+    (
+        "LOGLEVEL",
+        r"[Aa]lert|ALERT|[Tt]race|TRACE|[Dd]ebug|DEBUG|[Nn]otice|NOTICE|[Ii]nfo(?:rmation)?|INFO(?:RMATION)?|[Ww]arn(?:ing)?|WARN(?:ING)?|[Ee]rr(?:or)?|ERR(?:OR)?|[Cc]rit(?:ical)?|CRIT(?:ICAL)?|[Ff]atal|FATAL|[Ss]evere|SEVERE|EMERG(?:ENCY)?|[Ee]merg(?:ency)?",
+    ),
+    ("HTTPDUSER", r"%{EMAILADDRESS}|%{USER}"),
+    (
+        "COMMONAPACHELOG",
+        r#"%{IPORHOST:clientip} %{HTTPDUSER:ident} %{USER:auth} \[%{HTTPDATE:timestamp}\] "(?:%{WORD:verb} %{NOTSPACE:request}(?: HTTP/%{NUMBER:httpversion})?|%{DATA:rawrequest})" %{NUMBER:response} (?:%{NUMBER:bytes}|-)"#,
+    ),
+    (
+        "COMBINEDAPACHELOG",
+        r"%{COMMONAPACHELOG} %{QS:referrer} %{QS:agent}",
+    ),
+];
+
+/// Returns whether `name` can be defined, that is, it's made of ASCII
+/// letters, digits and `_`.
+pub fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_')
+}
+
+/// The longest expanded pattern, as definitions referencing another one
+/// several times double their length at every level.
+const MAX_EXPANDED_LEN: usize = 1 << 20;
+
+/// Replaces every `%{NAME}` in `pattern` with the definition of `NAME`, in
+/// a non-capturing group, and every `%{NAME:field}` with it in a group
+/// named `field`. Definitions are expanded recursively, up to
+/// `MAX_EXPANDED_LEN` bytes.
+pub fn expand(
+    pattern: &str,
+    definitions: &BTreeMap<String, String>,
+) -> Result<String, PatternError> {
+    let mut expanded = String::with_capacity(pattern.len());
+    expand_into(pattern, definitions, &mut Vec::new(), &mut expanded)?;
+    Ok(expanded)
+}
+
+/// Expands `pattern` into `expanded`, where `stack` holds the names of the
+/// definitions being expanded, to detect the ones that reference
+/// themselves.
+fn expand_into<'d>(
+    pattern: &str,
+    definitions: &'d BTreeMap<String, String>,
+    stack: &mut Vec<&'d str>,
+    expanded: &mut String,
+) -> Result<(), PatternError> {
+    let mut rest = 0;
+    while let Some(found) = pattern[rest..].find("%{") {
+        let start = rest + found;
+        expanded.push_str(&pattern[rest..start]);
+        let end = match pattern[start..].find('}') {
+            Some(close) => start + close + 1,
+            None => {
+                return Err(error(
+                    stack,
+                    pattern,
+                    String::from("unclosed reference, use `%\\{` to match `%{` literally"),
+                    start,
+                    pattern.len(),
+                ))
+            }
+        };
+
+        // `NAME`, `NAME:field` or `NAME:field:type`, where the type
+        // conversions of Logstash are accepted and ignored
+        let mut parts = pattern[start + 2..end - 1].split(':');
+        let name = parts.next().unwrap_or_default();
+        let field = parts.next();
+        let conversion = parts.next();
+        if !is_name(name)
+            || field == Some("")
+            || !matches!(conversion, None | Some("int") | Some("float"))
+            || parts.next().is_some()
+        {
+            return Err(error(
+                stack,
+                pattern,
+                String::from("invalid reference, expected `%{NAME}` or `%{NAME:field}`"),
+                start,
+                end,
+            ));
+        }
+        let (name, definition) = match definitions.get_key_value(name) {
+            Some((name, definition)) => (name.as_str(), definition),
+            None => {
+                return Err(error(
+                    stack,
+                    pattern,
+                    format!("unknown pattern {}", name),
+                    start,
+                    end,
+                ))
+            }
+        };
+        if expanded.len() > MAX_EXPANDED_LEN {
+            return Err(error(
+                stack,
+                pattern,
+                format!("pattern expands to more than {} bytes", MAX_EXPANDED_LEN),
+                start,
+                end,
+            ));
+        }
+        if let Some(first) = stack.iter().position(|expanding| *expanding == name) {
+            let mut cycle = stack[first..].join(" -> ");
+            cycle.push_str(" -> ");
+            cycle.push_str(name);
+            return Err(error(
+                stack,
+                pattern,
+                format!("pattern {} references itself: {}", name, cycle),
+                start,
+                end,
+            ));
+        }
+
+        match field {
+            Some(field) => {
+                expanded.push_str("(?P<");
+                expanded.push_str(field);
+                expanded.push('>');
+            }
+            None => expanded.push_str("(?:"),
+        }
+        stack.push(name);
+        expand_into(definition, definitions, stack, expanded)?;
+        stack.pop();
+        expanded.push(')');
+        rest = end;
+    }
+    expanded.push_str(&pattern[rest..]);
+    Ok(())
+}
+
+/// An error in the reference at `start..end` of `pattern`, which is the
+/// definition of the last name in `stack`, if any.
+fn error(stack: &[&str], pattern: &str, message: String, start: usize, end: usize) -> PatternError {
+    let message = match stack.last() {
+        Some(name) => format!("{} (in the definition of {})", message, name),
+        None => message,
+    };
+    PatternError::syntax(message, pattern, start, end)
+}
@@ -0,0 +1,1391 @@
+use regex_syntax::ast::{self, Ast};
+use regex_syntax::hir::{self, Class, ClassUnicode, ClassUnicodeRange, Hir, HirKind, Look};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+
+use crate::error::{PatternError, PatternErrorKind};
+use crate::options::RegexOptions;
+
+#[wasm_bindgen(typescript_custom_section)]
+const JS_REGEXP_TYPE: &'static str = r#"/**
+ * The source and flags of a javascript `RegExp`, ready to be used as
+ * `new RegExp(source, flags)`.
+ */
+export type JsRegExp = {
+  source: string
+  flags: string
+}"#;
+
+#[derive(Serialize)]
+pub struct JsRegExp {
+    pub source: String,
+    pub flags: String,
+}
+
+/// Characters that must be escaped outside of a character class.
+const SYNTAX_CHARACTERS: &str = "^$\\.*+?()[]{}|/";
+
+/// Characters that must be escaped inside of a character class.
+const CLASS_SYNTAX_CHARACTERS: &str = "^$\\.*+?()[]{}|/-";
+
+/// A unicode word character, as defined by `\w` in the rust syntax.
+const UNICODE_WORD: &str = r"[\p{Alphabetic}\p{M}\p{Nd}\p{Pc}\p{Join_Control}]";
+
+/// Translates a pattern in the rust syntax into an equivalent javascript
+/// `RegExp` (with the `u` flag).
+pub fn to_js_regexp(pattern: &str) -> Result<JsRegExp, PatternError> {
+    let ast = ast::parse::Parser::new().parse(pattern)?;
+    ast::visit(&ast, CaptureNameVisitor { pattern })?;
+
+    // the translator only accepts patterns matching valid UTF-8, so the
+    // constructs javascript can't match, like `(?-u:\xFF)`, are rejected
+    // with their span, and the HIR can always be written
+    let hir = hir::translate::Translator::new().translate(pattern, &ast)?;
+    let mut source = String::new();
+    write_hir(&mut source, &hir);
+
+    Ok(JsRegExp {
+        source,
+        flags: String::from("u"),
+    })
+}
+
+/// Rejects capture names that are valid in rust but not in javascript.
+struct CaptureNameVisitor<'p> {
+    pattern: &'p str,
+}
+
+impl<'p> ast::Visitor for CaptureNameVisitor<'p> {
+    type Output = ();
+    type Err = PatternError;
+
+    fn finish(self) -> Result<(), PatternError> {
+        Ok(())
+    }
+
+    fn visit_pre(&mut self, ast: &Ast) -> Result<(), PatternError> {
+        if let Ast::Group(group) = ast {
+            if let ast::GroupKind::CaptureName { name, .. } = &group.kind {
+                if name.name.contains(['.', '[', ']'].as_ref()) {
+                    return Err(PatternError::new(
+                        PatternErrorKind::Unsupported,
+                        format!(
+                            "capture group name '{}' is not a valid javascript identifier",
+                            name.name
+                        ),
+                        self.pattern,
+                        name.span,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_hir(out: &mut String, hir: &Hir) {
+    match hir.kind() {
+        HirKind::Empty => {}
+        HirKind::Literal(hir::Literal(bytes)) => {
+            for c in String::from_utf8_lossy(bytes).chars() {
+                write_char(out, c, SYNTAX_CHARACTERS);
+            }
+        }
+        HirKind::Class(class) => write_class(out, class),
+        HirKind::Look(look) => write_look(out, *look),
+        HirKind::Repetition(rep) => {
+            write_atom(out, &rep.sub);
+            match (rep.min, rep.max) {
+                (0, None) => out.push('*'),
+                (1, None) => out.push('+'),
+                (0, Some(1)) => out.push('?'),
+                (min, None) => {
+                    let _ = write!(out, "{{{},}}", min);
+                }
+                (min, Some(max)) if min == max => {
+                    let _ = write!(out, "{{{}}}", min);
+                }
+                (min, Some(max)) => {
+                    let _ = write!(out, "{{{},{}}}", min, max);
+                }
+            }
+            if !rep.greedy {
+                out.push('?');
+            }
+        }
+        HirKind::Capture(capture) => {
+            match &capture.name {
+                Some(name) => {
+                    let _ = write!(out, "(?<{}>", name);
+                }
+                None => out.push('('),
+            }
+            write_hir(out, &capture.sub);
+            out.push(')');
+        }
+        HirKind::Concat(subs) => {
+            for sub in subs {
+                if let HirKind::Alternation(_) = sub.kind() {
+                    out.push_str("(?:");
+                    write_hir(out, sub);
+                    out.push(')');
+                } else {
+                    write_hir(out, sub);
+                }
+            }
+        }
+        HirKind::Alternation(subs) => {
+            for (i, sub) in subs.iter().enumerate() {
+                if i > 0 {
+                    out.push('|');
+                }
+                write_hir(out, sub);
+            }
+        }
+    }
+}
+
+/// Writes `hir` so it can be quantified, wrapping it in a non-capturing
+/// group when needed.
+fn write_atom(out: &mut String, hir: &Hir) {
+    let atomic = match hir.kind() {
+        HirKind::Literal(hir::Literal(bytes)) => {
+            std::str::from_utf8(bytes).is_ok_and(|text| text.chars().count() == 1)
+        }
+        HirKind::Class(_) | HirKind::Capture(_) => true,
+        _ => false,
+    };
+
+    if atomic {
+        write_hir(out, hir);
+    } else {
+        out.push_str("(?:");
+        write_hir(out, hir);
+        out.push(')');
+    }
+}
+
+fn write_char(out: &mut String, c: char, syntax: &str) {
+    match c {
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        '\x0B' => out.push_str("\\v"),
+        '\x0C' => out.push_str("\\f"),
+        c if syntax.contains(c) => {
+            out.push('\\');
+            out.push(c);
+        }
+        ' '..='~' => out.push(c),
+        c if c.is_alphanumeric() => out.push(c),
+        c => {
+            let _ = write!(out, "\\u{{{:X}}}", c as u32);
+        }
+    }
+}
+
+fn write_class(out: &mut String, class: &Class) {
+    // byte classes only have ASCII bytes, which are the same characters
+    let class = match class {
+        Class::Unicode(class) => class.clone(),
+        Class::Bytes(class) => ClassUnicode::new(class.iter().map(|range| {
+            ClassUnicodeRange::new(char::from(range.start()), char::from(range.end()))
+        })),
+    };
+
+    let ranges = class.ranges();
+    if ranges.len() == 1 && ranges[0].start() == ranges[0].end() {
+        write_char(out, ranges[0].start(), SYNTAX_CHARACTERS);
+        return;
+    }
+
+    // write the shortest of the class and its negation
+    let mut negated = class.clone();
+    negated.negate();
+    let (prefix, ranges) = if negated.ranges().len() < ranges.len() {
+        ("[^", negated.ranges())
+    } else {
+        ("[", ranges)
+    };
+
+    out.push_str(prefix);
+    for range in ranges {
+        write_char(out, range.start(), CLASS_SYNTAX_CHARACTERS);
+        if range.start() != range.end() {
+            out.push('-');
+            write_char(out, range.end(), CLASS_SYNTAX_CHARACTERS);
+        }
+    }
+    out.push(']');
+}
+
+fn write_look(out: &mut String, look: Look) {
+    let w = UNICODE_WORD;
+    match look {
+        Look::Start => out.push('^'),
+        Look::End => out.push('$'),
+        Look::StartLF => out.push_str(r"(?<![^\n])"),
+        Look::EndLF => out.push_str(r"(?![^\n])"),
+        Look::StartCRLF => out.push_str(r"(?<![^\n\r])(?<!\r(?=\n))"),
+        Look::EndCRLF => out.push_str(r"(?![^\n\r])(?!(?<=\r)\n)"),
+        Look::WordAscii => out.push_str(r"\b"),
+        Look::WordAsciiNegate => out.push_str(r"\B"),
+        Look::WordStartAscii => out.push_str(r"(?<!\w)(?=\w)"),
+        Look::WordEndAscii => out.push_str(r"(?<=\w)(?!\w)"),
+        Look::WordStartHalfAscii => out.push_str(r"(?<!\w)"),
+        Look::WordEndHalfAscii => out.push_str(r"(?!\w)"),
+        Look::WordUnicode => {
+            let _ = write!(out, "(?:(?<={w})(?!{w})|(?<!{w})(?={w}))", w = w);
+        }
+        Look::WordUnicodeNegate => {
+            let _ = write!(out, "(?:(?<={w})(?={w})|(?<!{w})(?!{w}))", w = w);
+        }
+        Look::WordStartUnicode => {
+            let _ = write!(out, "(?<!{w})(?={w})", w = w);
+        }
+        Look::WordEndUnicode => {
+            let _ = write!(out, "(?<={w})(?!{w})", w = w);
+        }
+        Look::WordStartHalfUnicode => {
+            let _ = write!(out, "(?<!{w})", w = w);
+        }
+        Look::WordEndHalfUnicode => {
+            let _ = write!(out, "(?!{w})", w = w);
+        }
+    }
+}
+
+/// The characters matched by `\s` in javascript.
+const JS_WHITESPACE: &str = r"\t\n\x0B\x0C\r \x{A0}\x{1680}\x{2000}-\x{200A}\x{2028}\x{2029}\x{202F}\x{205F}\x{3000}\x{FEFF}";
+
+/// The characters matched by `.` in javascript without the `s` flag.
+const JS_DOT: &str = r"[^\n\r\x{2028}\x{2029}]";
+
+/// The characters matched by `\w` in javascript.
+const JS_WORD: &str = "0-9A-Za-z_";
+
+/// The characters matched by `\d` in javascript.
+const JS_DIGIT: &str = "0-9";
+
+/// Translates the source and flags of a javascript `RegExp` into an
+/// equivalent pattern in the rust syntax and the options it must be compiled
+/// with.
+pub fn from_js_regexp(source: &str, flags: &str) -> Result<(String, RegexOptions), PatternError> {
+    let flags = JsFlags::parse(flags)?;
+    // without the `u` flag, case insensitive matching compares characters
+    // by `toUpperCase`, which is written as classes instead
+    let options = RegexOptions {
+        case_insensitive: flags.ignore_case && (flags.unicode || flags.unicode_sets),
+        dot_matches_new_line: flags.dot_all,
+        ..RegexOptions::default()
+    };
+
+    let mut parser = JsParser::new(source, &flags);
+    let mut pattern = String::new();
+    parser.disjunction(&mut pattern)?;
+    if let Some((offset, c)) = parser.peek() {
+        // the only way to stop parsing early is an unmatched `)`
+        return Err(PatternError::syntax(
+            format!("unmatched '{}'", c),
+            source,
+            offset,
+            offset + c.len_utf8(),
+        ));
+    }
+
+    Ok((pattern, options))
+}
+
+#[derive(Default)]
+struct JsFlags {
+    ignore_case: bool,
+    multiline: bool,
+    dot_all: bool,
+    unicode: bool,
+    unicode_sets: bool,
+}
+
+impl JsFlags {
+    fn parse(flags: &str) -> Result<JsFlags, PatternError> {
+        let mut parsed = JsFlags::default();
+        for (offset, c) in flags.char_indices() {
+            if flags[..offset].contains(c) {
+                return Err(PatternError::syntax(
+                    format!("duplicate flag '{}'", c),
+                    flags,
+                    offset,
+                    offset + c.len_utf8(),
+                ));
+            }
+            match c {
+                // the kind of search is picked by the method that is called,
+                // and match indices are always reported
+                'g' | 'd' => {}
+                'i' => parsed.ignore_case = true,
+                'm' => parsed.multiline = true,
+                's' => parsed.dot_all = true,
+                'u' => parsed.unicode = true,
+                'v' => parsed.unicode_sets = true,
+                // a sticky search starts at `lastIndex`, which `\A` can't
+                // express once the search continues after the first match
+                'y' => {
+                    return Err(PatternError::unsupported(
+                        "the 'y' flag is not supported",
+                        flags,
+                        offset,
+                        offset + 1,
+                    ))
+                }
+                c => {
+                    return Err(PatternError::syntax(
+                        format!("invalid flag '{}'", c),
+                        flags,
+                        offset,
+                        offset + c.len_utf8(),
+                    ))
+                }
+            }
+        }
+        if parsed.unicode && parsed.unicode_sets {
+            let offset = flags.find('v').unwrap_or(0);
+            return Err(PatternError::syntax(
+                "the 'u' and 'v' flags can't be combined",
+                flags,
+                offset,
+                offset + 1,
+            ));
+        }
+        Ok(parsed)
+    }
+}
+
+/// An item of a character class: either a single character or a set of
+/// characters already written in the rust syntax.
+enum ClassAtom {
+    Char(char),
+    Set(String),
+}
+
+/// A recursive descent parser of the javascript pattern syntax that writes
+/// the equivalent rust pattern as it goes.
+struct JsParser<'p> {
+    pattern: &'p str,
+    chars: Vec<(usize, char)>,
+    index: usize,
+    /// Whether the pattern is parsed with the `u` or `v` flag.
+    unicode: bool,
+    /// Whether the pattern is parsed with the `v` flag.
+    unicode_sets: bool,
+    /// Whether `\k` starts a named backreference, even without the `u` flag.
+    named_groups: bool,
+    /// The number of capture groups of the whole pattern.
+    group_count: usize,
+    group_names: Vec<String>,
+    /// The state of the `i`, `m` and `s` flags, which change in modifier
+    /// groups.
+    ignore_case: bool,
+    multiline: bool,
+    dot_all: bool,
+    global_dot_all: bool,
+}
+
+impl<'p> JsParser<'p> {
+    fn new(pattern: &'p str, flags: &JsFlags) -> JsParser<'p> {
+        let chars: Vec<(usize, char)> = pattern.char_indices().collect();
+        let (group_count, named_groups) = count_groups(&chars);
+        JsParser {
+            pattern,
+            chars,
+            index: 0,
+            unicode: flags.unicode || flags.unicode_sets,
+            unicode_sets: flags.unicode_sets,
+            named_groups,
+            group_count,
+            group_names: Vec::new(),
+            ignore_case: flags.ignore_case,
+            multiline: flags.multiline,
+            dot_all: flags.dot_all,
+            global_dot_all: flags.dot_all,
+        }
+    }
+
+    fn peek(&self) -> Option<(usize, char)> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.peek().map(|(_, c)| c)
+    }
+
+    fn peek_is(&self, expected: &str) -> bool {
+        let mut chars = self.chars[self.index..].iter().map(|(_, c)| *c);
+        expected.chars().all(|c| chars.next() == Some(c))
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek_char();
+        if c.is_some() {
+            self.index += 1;
+        }
+        c
+    }
+
+    /// The byte offset of the current position.
+    fn offset(&self) -> usize {
+        self.offset_of(self.index)
+    }
+
+    fn offset_of(&self, index: usize) -> usize {
+        self.chars
+            .get(index)
+            .map(|(offset, _)| *offset)
+            .unwrap_or(self.pattern.len())
+    }
+
+    fn syntax<M: ToString>(&self, message: M, start: usize) -> PatternError {
+        PatternError::syntax(message, self.pattern, self.offset_of(start), self.offset())
+    }
+
+    fn unsupported<M: ToString>(&self, message: M, start: usize) -> PatternError {
+        PatternError::unsupported(message, self.pattern, self.offset_of(start), self.offset())
+    }
+
+    fn disjunction(&mut self, out: &mut String) -> Result<(), PatternError> {
+        self.alternative(out)?;
+        while self.peek_char() == Some('|') {
+            self.bump();
+            out.push('|');
+            self.alternative(out)?;
+        }
+        Ok(())
+    }
+
+    fn alternative(&mut self, out: &mut String) -> Result<(), PatternError> {
+        while let Some(c) = self.peek_char() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            self.term(out)?;
+        }
+        Ok(())
+    }
+
+    fn term(&mut self, out: &mut String) -> Result<(), PatternError> {
+        let start = self.index;
+        let quantifiable = match self.bump() {
+            // in multi-line mode, `\r`, `\u2028` and `\u2029` also end lines,
+            // while rust only breaks lines at `\n`
+            Some(c @ '^') | Some(c @ '$') if self.multiline => {
+                return Err(self.unsupported(
+                    format!("'{}' is not supported in multi-line mode", c),
+                    start,
+                ));
+            }
+            Some(c @ '^') | Some(c @ '$') => {
+                out.push(c);
+                false
+            }
+            Some('.') => {
+                if self.dot_all && self.global_dot_all {
+                    out.push('.');
+                } else if self.dot_all {
+                    out.push_str("(?s:.)");
+                } else {
+                    out.push_str(JS_DOT);
+                }
+                true
+            }
+            Some('(') => self.group(out, start)?,
+            Some('[') => {
+                self.class(out, start)?;
+                true
+            }
+            Some('\\') => self.atom_escape(out, start)?,
+            Some('*') | Some('+') | Some('?') => {
+                return Err(self.syntax("nothing to repeat", start));
+            }
+            Some('{') if self.unicode || self.quantifier_bounds(start).is_some() => {
+                return Err(self.syntax("nothing to repeat", start));
+            }
+            Some(c @ ']') | Some(c @ '}') if self.unicode => {
+                return Err(self.syntax(format!("lone quantifier bracket '{}'", c), start));
+            }
+            Some(c) => {
+                self.write_char(out, c);
+                true
+            }
+            None => return Ok(()),
+        };
+
+        self.quantifier(out, start, quantifiable)
+    }
+
+    fn quantifier(
+        &mut self,
+        out: &mut String,
+        start: usize,
+        quantifiable: bool,
+    ) -> Result<(), PatternError> {
+        let quantifier_start = self.index;
+        match self.peek_char() {
+            Some(c @ '*') | Some(c @ '+') | Some(c @ '?') => {
+                self.bump();
+                out.push(c);
+            }
+            Some('{') => match self.quantifier_bounds(self.index) {
+                Some((min, max, len)) => {
+                    self.index += len;
+                    match max {
+                        Some(max) if max < min => {
+                            return Err(self.syntax(
+                                "numbers out of order in {} quantifier",
+                                quantifier_start,
+                            ));
+                        }
+                        Some(max) if max == min => {
+                            let _ = write!(out, "{{{}}}", min);
+                        }
+                        Some(max) => {
+                            let _ = write!(out, "{{{},{}}}", min, max);
+                        }
+                        None => {
+                            let _ = write!(out, "{{{},}}", min);
+                        }
+                    }
+                }
+                None if self.unicode => {
+                    self.index += 1;
+                    return Err(self.syntax("incomplete quantifier", quantifier_start));
+                }
+                // a literal `{`, parsed by the next term
+                None => return Ok(()),
+            },
+            _ => return Ok(()),
+        }
+
+        if !quantifiable {
+            return Err(self.syntax("nothing to repeat", start));
+        }
+        if self.peek_char() == Some('?') {
+            self.bump();
+            out.push('?');
+        }
+        Ok(())
+    }
+
+    /// Parses `{n}`, `{n,}` or `{n,m}` at `index`, without consuming it,
+    /// returning the bounds and the number of characters.
+    fn quantifier_bounds(&self, index: usize) -> Option<(u32, Option<u32>, usize)> {
+        let rest: String = self.chars[index..].iter().map(|(_, c)| *c).collect();
+        let end = rest.find('}')?;
+        let body = rest.get(1..end)?;
+        let (min, max) = match body.find(',') {
+            Some(comma) => (&body[..comma], Some(&body[comma + 1..])),
+            None => (body, None),
+        };
+        let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+        if !digits(min) {
+            return None;
+        }
+        let min = min.parse().unwrap_or(u32::MAX);
+        let max = match max {
+            None => Some(min),
+            Some("") => None,
+            Some(max) if digits(max) => Some(max.parse().unwrap_or(u32::MAX)),
+            Some(_) => return None,
+        };
+        Some((min, max, rest[..=end].chars().count()))
+    }
+
+    /// Parses a group after its opening `(`, returns whether it can be
+    /// quantified.
+    fn group(&mut self, out: &mut String, start: usize) -> Result<bool, PatternError> {
+        let (ignore_case, multiline, dot_all) = (self.ignore_case, self.multiline, self.dot_all);
+        let mut lookaround = None;
+
+        if self.peek_char() != Some('?') {
+            out.push('(');
+        } else if self.peek_is("?:") {
+            self.index += 2;
+            out.push_str("(?:");
+        } else if self.peek_is("?=") || self.peek_is("?!") {
+            self.index += 2;
+            lookaround = Some("lookahead");
+        } else if self.peek_is("?<=") || self.peek_is("?<!") {
+            self.index += 3;
+            lookaround = Some("lookbehind");
+        } else if self.peek_is("?<") {
+            self.index += 2;
+            let name = self.group_name(start)?;
+            let _ = write!(out, "(?<{}>", name);
+        } else {
+            self.bump();
+            self.modifiers(out, start)?;
+        }
+
+        let mut body = String::new();
+        self.disjunction(&mut body)?;
+        if self.bump() != Some(')') {
+            return Err(self.syntax("unclosed group", start));
+        }
+        self.ignore_case = ignore_case;
+        self.multiline = multiline;
+        self.dot_all = dot_all;
+
+        if let Some(kind) = lookaround {
+            return Err(self.unsupported(format!("{} assertions are not supported", kind), start));
+        }
+        out.push_str(&body);
+        out.push(')');
+        Ok(true)
+    }
+
+    /// Parses the name of a capture group, after its `(?<`.
+    fn group_name(&mut self, start: usize) -> Result<String, PatternError> {
+        let name_start = self.index;
+        let mut name = String::new();
+        loop {
+            match self.peek_char() {
+                Some('>') => break,
+                Some(c) if c == '$' || c == '\\' => {
+                    self.bump();
+                    return Err(self.unsupported(
+                        format!("'{}' can't be used in the name of a capture group", c),
+                        self.index - 1,
+                    ));
+                }
+                Some(c) if c == '_' || c.is_alphanumeric() => {
+                    self.bump();
+                    name.push(c);
+                }
+                Some(_) => {
+                    self.bump();
+                    return Err(self.syntax("invalid capture group name", self.index - 1));
+                }
+                None => return Err(self.syntax("unclosed capture group name", start)),
+            }
+        }
+
+        if name.chars().next().is_none_or(char::is_numeric) {
+            return Err(self.syntax("invalid capture group name", name_start));
+        }
+        if self.group_names.contains(&name) {
+            return Err(self.unsupported(
+                format!("duplicate capture group name '{}'", name),
+                name_start,
+            ));
+        }
+        self.bump();
+        self.group_names.push(name.clone());
+        Ok(name)
+    }
+
+    /// Parses the flags of a modifier group like `(?i:...)` or `(?-i:...)`,
+    /// after its `(?`.
+    fn modifiers(&mut self, out: &mut String, start: usize) -> Result<(), PatternError> {
+        let mut added = String::new();
+        let mut removed = String::new();
+        let mut removing = false;
+        loop {
+            match self.bump() {
+                Some(':') => break,
+                Some('-') if !removing => removing = true,
+                Some(c @ 'i') | Some(c @ 'm') | Some(c @ 's') => {
+                    if added.contains(c) || removed.contains(c) {
+                        return Err(self.syntax(format!("repeated flag '{}'", c), start));
+                    }
+                    if removing {
+                        removed.push(c);
+                    } else {
+                        added.push(c);
+                    }
+                }
+                _ => return Err(self.syntax("invalid group", start)),
+            }
+        }
+        if added.is_empty() && removed.is_empty() {
+            return Err(self.syntax("invalid group", start));
+        }
+
+        if added.contains('i') || removed.contains('i') {
+            self.ignore_case = added.contains('i');
+        }
+        if added.contains('m') || removed.contains('m') {
+            self.multiline = added.contains('m');
+        }
+        if added.contains('s') || removed.contains('s') {
+            self.dot_all = added.contains('s');
+        }
+
+        // `.`, `^` and `$` are always written explicitly, and so is case
+        // folding without the `u` flag, so only `i` in unicode mode matters
+        let kept = |c: &char| *c == 'i' && self.unicode;
+        let added: String = added.chars().filter(kept).collect();
+        let removed: String = removed.chars().filter(kept).collect();
+        out.push_str("(?");
+        out.push_str(&added);
+        if !removed.is_empty() {
+            out.push('-');
+            out.push_str(&removed);
+        }
+        out.push(':');
+        Ok(())
+    }
+
+    /// Parses an escape outside of a character class, after its `\`, returns
+    /// whether it can be quantified.
+    fn atom_escape(&mut self, out: &mut String, start: usize) -> Result<bool, PatternError> {
+        match self.peek_char() {
+            Some('b') => {
+                self.bump();
+                out.push_str(r"(?-u:\b)");
+                return Ok(false);
+            }
+            Some('B') => {
+                self.bump();
+                out.push_str(r"(?-u:\B)");
+                return Ok(false);
+            }
+            Some('1'..='9') => {
+                let digits_start = self.index;
+                let mut number = 0usize;
+                while let Some(digit) = self.peek_char().and_then(|c| c.to_digit(10)) {
+                    number = number.saturating_mul(10).saturating_add(digit as usize);
+                    self.bump();
+                }
+                if self.unicode || number <= self.group_count {
+                    if number > self.group_count {
+                        return Err(self.syntax("invalid escape", start));
+                    }
+                    return Err(self.unsupported("backreferences are not supported", start));
+                }
+                // a legacy octal escape, or an identity escape of `8` and `9`
+                self.index = digits_start;
+                let c = self
+                    .legacy_octal()
+                    .unwrap_or_else(|| self.bump().unwrap_or('\\'));
+                self.write_char(out, c);
+                return Ok(true);
+            }
+            Some('k') if self.unicode || self.named_groups => {
+                self.bump();
+                if self.bump() != Some('<') {
+                    return Err(self.syntax("invalid named reference", start));
+                }
+                while let Some(c) = self.bump() {
+                    if c == '>' {
+                        return Err(self.unsupported("backreferences are not supported", start));
+                    }
+                }
+                return Err(self.syntax("invalid named reference", start));
+            }
+            _ => {}
+        }
+
+        match self.class_escape(start)? {
+            ClassAtom::Char(c) => self.write_char(out, c),
+            ClassAtom::Set(set) if self.folds_case() => {
+                let set = if set.starts_with('[') {
+                    set
+                } else {
+                    format!("[{}]", set)
+                };
+                self.write_folded(out, &set, false, start)?;
+            }
+            ClassAtom::Set(set) if set.starts_with('[') => out.push_str(&set),
+            ClassAtom::Set(set) => {
+                let _ = write!(out, "[{}]", set);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Parses an escape that can be used both inside and outside of a
+    /// character class, after its `\`.
+    fn class_escape(&mut self, start: usize) -> Result<ClassAtom, PatternError> {
+        let c = match self.bump() {
+            Some(c) => c,
+            None => return Err(self.syntax("\\ at end of pattern", start)),
+        };
+        let atom = match c {
+            'd' => ClassAtom::Set(String::from(JS_DIGIT)),
+            'D' => ClassAtom::Set(format!("[^{}]", JS_DIGIT)),
+            'w' => ClassAtom::Set(String::from(JS_WORD)),
+            'W' => ClassAtom::Set(format!("[^{}]", JS_WORD)),
+            's' => ClassAtom::Set(String::from(JS_WHITESPACE)),
+            'S' => ClassAtom::Set(format!("[^{}]", JS_WHITESPACE)),
+            't' => ClassAtom::Char('\t'),
+            'n' => ClassAtom::Char('\n'),
+            'v' => ClassAtom::Char('\x0B'),
+            'f' => ClassAtom::Char('\x0C'),
+            'r' => ClassAtom::Char('\r'),
+            'c' => match self.peek_char() {
+                Some(letter) if letter.is_ascii_alphabetic() => {
+                    self.bump();
+                    ClassAtom::Char(char::from(letter as u8 % 32))
+                }
+                _ if self.unicode => return Err(self.syntax("invalid unicode escape", start)),
+                // `\c` without a control letter is a literal backslash
+                _ => {
+                    self.index -= 1;
+                    ClassAtom::Char('\\')
+                }
+            },
+            '0' if !self.peek_char().is_some_and(|c| c.is_ascii_digit()) => ClassAtom::Char('\0'),
+            '0'..='9' if !self.unicode => {
+                self.index -= 1;
+                match self.legacy_octal() {
+                    Some(c) => ClassAtom::Char(c),
+                    None => ClassAtom::Char(self.bump().unwrap_or('\\')),
+                }
+            }
+            'x' => match self.hex_digits(2) {
+                Some(value) => ClassAtom::Char(char::from(value as u8)),
+                None if self.unicode => return Err(self.syntax("invalid escape", start)),
+                None => ClassAtom::Char('x'),
+            },
+            'u' => match self.unicode_escape(start)? {
+                Some(c) => ClassAtom::Char(c),
+                None => ClassAtom::Char('u'),
+            },
+            'p' | 'P' if self.unicode => ClassAtom::Set(self.property(start)?),
+            c if is_syntax_character(c) || c == '/' => ClassAtom::Char(c),
+            c if self.unicode => {
+                return Err(self.syntax(format!("invalid escape '\\{}'", c), start));
+            }
+            c => ClassAtom::Char(c),
+        };
+        Ok(atom)
+    }
+
+    /// Parses a legacy octal escape (without the `u` flag) up to `\377`.
+    fn legacy_octal(&mut self) -> Option<char> {
+        let mut value = 0u32;
+        let mut len = 0;
+        while let Some(digit) = self.peek_char().and_then(|c| c.to_digit(8)) {
+            if len == 3 || value * 8 + digit > 0o377 {
+                break;
+            }
+            value = value * 8 + digit;
+            len += 1;
+            self.bump();
+        }
+        if len == 0 {
+            None
+        } else {
+            char::from_u32(value)
+        }
+    }
+
+    /// Parses exactly `len` hexadecimal digits, consuming them only when
+    /// all of them are present.
+    fn hex_digits(&mut self, len: usize) -> Option<u32> {
+        let digits = self.chars.get(self.index..self.index + len)?;
+        let mut value = 0;
+        for (_, c) in digits {
+            value = value * 16 + c.to_digit(16)?;
+        }
+        self.index += len;
+        Some(value)
+    }
+
+    /// Parses `\uHHHH`, a surrogate pair `\uHHHH\uHHHH` or, in unicode mode,
+    /// `\u{H...}`, after its `\u`. Returns `None` for an identity escape.
+    fn unicode_escape(&mut self, start: usize) -> Result<Option<char>, PatternError> {
+        if self.unicode && self.peek_char() == Some('{') {
+            self.bump();
+            let mut value = 0u32;
+            let mut len = 0;
+            while let Some(digit) = self.peek_char().and_then(|c| c.to_digit(16)) {
+                self.bump();
+                value = value.saturating_mul(16).saturating_add(digit);
+                len += 1;
+            }
+            if len == 0 || self.bump() != Some('}') || value > 0x10FFFF {
+                return Err(self.syntax("invalid unicode escape", start));
+            }
+            return match char::from_u32(value) {
+                Some(c) => Ok(Some(c)),
+                None => Err(self.unsupported("lone surrogates are not supported", start)),
+            };
+        }
+
+        let high = match self.hex_digits(4) {
+            Some(value) => value,
+            None if self.unicode => return Err(self.syntax("invalid unicode escape", start)),
+            None => return Ok(None),
+        };
+        if let Some(c) = char::from_u32(high) {
+            return Ok(Some(c));
+        }
+        if (0xD800..0xDC00).contains(&high) && self.peek_is("\\u") {
+            let before = self.index;
+            self.index += 2;
+            match self.hex_digits(4) {
+                Some(low) if (0xDC00..0xE000).contains(&low) => {
+                    let value = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                    return Ok(char::from_u32(value));
+                }
+                _ => self.index = before,
+            }
+        }
+        Err(self.unsupported("lone surrogates are not supported", start))
+    }
+
+    /// Parses `\p{...}` or `\P{...}`, after its `p`, and checks that the
+    /// property is known to rust.
+    fn property(&mut self, start: usize) -> Result<String, PatternError> {
+        if self.bump() != Some('{') {
+            return Err(self.syntax("invalid property name", start));
+        }
+        loop {
+            match self.bump() {
+                Some('}') => break,
+                Some(c) if c.is_ascii_alphanumeric() || c == '_' || c == '=' => {}
+                _ => return Err(self.syntax("invalid property name", start)),
+            }
+        }
+
+        let escape = &self.pattern[self.offset_of(start)..self.offset()];
+        if regex_syntax::Parser::new().parse(escape).is_err() {
+            return Err(
+                self.unsupported(format!("the property '{}' is not supported", escape), start)
+            );
+        }
+        Ok(escape.to_owned())
+    }
+
+    /// Parses a character class, after its `[`.
+    fn class(&mut self, out: &mut String, start: usize) -> Result<(), PatternError> {
+        let negated = self.peek_char() == Some('^');
+        if negated {
+            self.bump();
+        }
+        if self.peek_char() == Some(']') {
+            self.bump();
+            // `[]` never matches, `[^]` matches anything
+            out.push_str(if negated {
+                "(?s:.)"
+            } else {
+                r"[^\x00-\x{10FFFF}]"
+            });
+            return Ok(());
+        }
+
+        // the class is folded before it's negated, so `[^a]` doesn't match
+        // `A` either
+        if self.folds_case() {
+            let mut class = String::from("[");
+            self.class_ranges(&mut class, start)?;
+            class.push(']');
+            return self.write_folded(out, &class, negated, start);
+        }
+
+        out.push_str(if negated { "[^" } else { "[" });
+        if self.unicode_sets {
+            self.class_set(out, start)?;
+        } else {
+            self.class_ranges(out, start)?;
+        }
+        out.push(']');
+        Ok(())
+    }
+
+    /// Whether characters are compared by `toUpperCase`, i.e. the `i` flag
+    /// without the `u` flag.
+    fn folds_case(&self) -> bool {
+        self.ignore_case && !self.unicode
+    }
+
+    /// Writes `c` as a literal, or as the class of the characters it's
+    /// equal to when `folds_case`.
+    fn write_char(&self, out: &mut String, c: char) {
+        if !self.folds_case() {
+            return write_rust_char(out, c, false);
+        }
+        let mut class = ClassUnicode::new([ClassUnicodeRange::new(c, c)]);
+        fold_case(&mut class);
+        write_rust_class(out, &class);
+    }
+
+    /// Writes `class`, a class in the rust syntax, with the characters equal
+    /// to its characters by `toUpperCase`, and negated afterwards when
+    /// `negated` is true.
+    fn write_folded(
+        &self,
+        out: &mut String,
+        class: &str,
+        negated: bool,
+        start: usize,
+    ) -> Result<(), PatternError> {
+        let hir = regex_syntax::Parser::new()
+            .parse(class)
+            .map_err(|_| self.syntax("invalid character class", start))?;
+        let mut class = match hir.into_kind() {
+            HirKind::Class(Class::Unicode(class)) => class,
+            HirKind::Literal(hir::Literal(bytes)) => {
+                let text = String::from_utf8_lossy(&bytes);
+                ClassUnicode::new(text.chars().map(|c| ClassUnicodeRange::new(c, c)))
+            }
+            _ => ClassUnicode::empty(),
+        };
+        fold_case(&mut class);
+        if negated {
+            class.negate();
+        }
+        write_rust_class(out, &class);
+        Ok(())
+    }
+
+    /// Parses the contents of a class without the `v` flag, up to its `]`.
+    fn class_ranges(&mut self, out: &mut String, start: usize) -> Result<(), PatternError> {
+        loop {
+            let atom_start = self.index;
+            let first = match self.class_atom(start)? {
+                Some(atom) => atom,
+                None => return Ok(()),
+            };
+            if self.peek_char() != Some('-') || self.peek_is("-]") {
+                write_class_atom(out, first);
+                continue;
+            }
+
+            self.bump();
+            let second = match self.class_atom(start)? {
+                Some(atom) => atom,
+                None => return Err(self.syntax("unterminated character class", start)),
+            };
+            match (first, second) {
+                (ClassAtom::Char(a), ClassAtom::Char(b)) => {
+                    if a > b {
+                        return Err(
+                            self.syntax("range out of order in character class", atom_start)
+                        );
+                    }
+                    write_class_atom(out, ClassAtom::Char(a));
+                    out.push('-');
+                    write_class_atom(out, ClassAtom::Char(b));
+                }
+                _ if self.unicode => {
+                    return Err(self.syntax("invalid character class", atom_start));
+                }
+                (first, second) => {
+                    write_class_atom(out, first);
+                    write_class_atom(out, ClassAtom::Char('-'));
+                    write_class_atom(out, second);
+                }
+            }
+        }
+    }
+
+    /// Parses a single item of a class without the `v` flag, returns `None`
+    /// at the closing `]`.
+    fn class_atom(&mut self, start: usize) -> Result<Option<ClassAtom>, PatternError> {
+        let escape_start = self.index;
+        match self.bump() {
+            None => Err(self.syntax("unterminated character class", start)),
+            Some(']') => Ok(None),
+            Some('\\') => match self.peek_char() {
+                Some('b') => {
+                    self.bump();
+                    Ok(Some(ClassAtom::Char('\x08')))
+                }
+                // `\c` also accepts digits and `_` inside of a class
+                Some('c') if !self.unicode => {
+                    let control = self.chars.get(self.index + 1).map(|(_, c)| *c);
+                    match control {
+                        Some(c) if c.is_ascii_alphanumeric() || c == '_' => {
+                            self.index += 2;
+                            Ok(Some(ClassAtom::Char(char::from(c as u8 % 32))))
+                        }
+                        _ => Ok(Some(ClassAtom::Char('\\'))),
+                    }
+                }
+                Some('k') if !self.unicode => {
+                    self.bump();
+                    Ok(Some(ClassAtom::Char('k')))
+                }
+                Some('-') if self.unicode => {
+                    self.bump();
+                    Ok(Some(ClassAtom::Char('-')))
+                }
+                Some('B') if self.unicode => {
+                    self.bump();
+                    Err(self.syntax("invalid class escape", escape_start))
+                }
+                Some('1'..='9') if self.unicode => {
+                    self.bump();
+                    Err(self.syntax("invalid class escape", escape_start))
+                }
+                _ => self.class_escape(escape_start).map(Some),
+            },
+            Some(c) => Ok(Some(ClassAtom::Char(c))),
+        }
+    }
+
+    /// Parses the contents of a class with the `v` flag, up to its `]`.
+    ///
+    /// Nested classes, `&&` and `--` are written as they are, since rust
+    /// supports the same set operations.
+    fn class_set(&mut self, out: &mut String, start: usize) -> Result<(), PatternError> {
+        let mut operands = 0;
+        let mut last_range = false;
+        let mut operator: Option<&str> = None;
+        loop {
+            let operator_start = self.index;
+            if self.peek_is("&&") || self.peek_is("--") {
+                let op = if self.peek_is("&&") { "&&" } else { "--" };
+                self.index += 2;
+                // ranges and unions must be nested to be used in a set operation
+                let valid = match operator {
+                    None => operands == 1 && !last_range,
+                    Some(current) => current == op,
+                };
+                out.push_str(op);
+                if !valid || self.class_set_operand(out, start)? != Some(false) {
+                    return Err(
+                        self.syntax("invalid set operation in character class", operator_start)
+                    );
+                }
+                operator = Some(op);
+                continue;
+            }
+
+            if operator.is_some() {
+                if self.bump() != Some(']') {
+                    return Err(
+                        self.syntax("invalid set operation in character class", operator_start)
+                    );
+                }
+                return Ok(());
+            }
+            match self.class_set_operand(out, start)? {
+                Some(range) => last_range = range,
+                None => return Ok(()),
+            }
+            operands += 1;
+        }
+    }
+
+    /// Parses a single operand of a class with the `v` flag, returns whether
+    /// it's a range, or `None` at the closing `]`.
+    fn class_set_operand(
+        &mut self,
+        out: &mut String,
+        start: usize,
+    ) -> Result<Option<bool>, PatternError> {
+        let first = match self.class_set_character(out, start)? {
+            Some(Some(c)) => c,
+            Some(None) => return Ok(Some(false)),
+            None => return Ok(None),
+        };
+        if self.peek_char() != Some('-') || self.peek_is("--") {
+            write_class_atom(out, ClassAtom::Char(first));
+            return Ok(Some(false));
+        }
+
+        let range_start = self.index;
+        self.bump();
+        let mut nested = String::new();
+        let last = match self.class_set_character(&mut nested, start)? {
+            Some(Some(c)) => c,
+            _ => return Err(self.syntax("invalid character class range", range_start)),
+        };
+        if first > last {
+            return Err(self.syntax("range out of order in character class", range_start));
+        }
+        write_class_atom(out, ClassAtom::Char(first));
+        out.push('-');
+        write_class_atom(out, ClassAtom::Char(last));
+        Ok(Some(true))
+    }
+
+    /// Parses a character of a class with the `v` flag. Nested classes and
+    /// escapes of sets are written to `out` directly and return
+    /// `Some(None)`, the closing `]` returns `None`.
+    fn class_set_character(
+        &mut self,
+        out: &mut String,
+        start: usize,
+    ) -> Result<Option<Option<char>>, PatternError> {
+        let atom_start = self.index;
+        match self.bump() {
+            None => Err(self.syntax("unterminated character class", start)),
+            Some(']') => Ok(None),
+            Some('[') => {
+                self.class(out, atom_start)?;
+                Ok(Some(None))
+            }
+            Some('\\') => match self.peek_char() {
+                Some('q') => {
+                    while let Some(c) = self.bump() {
+                        if c == '}' {
+                            break;
+                        }
+                    }
+                    Err(self
+                        .unsupported("strings in character classes are not supported", atom_start))
+                }
+                Some('b') => {
+                    self.bump();
+                    Ok(Some(Some('\x08')))
+                }
+                Some(c) if "&-!#%,:;<=>@`~".contains(c) => {
+                    self.bump();
+                    Ok(Some(Some(c)))
+                }
+                _ => match self.class_escape(atom_start)? {
+                    ClassAtom::Char(c) => Ok(Some(Some(c))),
+                    // nested, so it's a single operand of `&&` and `--`
+                    ClassAtom::Set(set) if set.starts_with('[') => {
+                        out.push_str(&set);
+                        Ok(Some(None))
+                    }
+                    ClassAtom::Set(set) => {
+                        let _ = write!(out, "[{}]", set);
+                        Ok(Some(None))
+                    }
+                },
+            },
+            Some(c) if "()[]{}/-|".contains(c) => Err(self.syntax(
+                format!("'{}' must be escaped in a character class", c),
+                atom_start,
+            )),
+            Some(c) => Ok(Some(Some(c))),
+        }
+    }
+}
+
+/// Returns the number of capture groups in a javascript pattern, and whether
+/// any of them is named.
+fn count_groups(chars: &[(usize, char)]) -> (usize, bool) {
+    let mut count = 0;
+    let mut named = false;
+    let mut in_class = false;
+    let mut chars = chars.iter().map(|(_, c)| *c).peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '(' if !in_class => {
+                if chars.peek() != Some(&'?') {
+                    count += 1;
+                    continue;
+                }
+                let mut lookahead = chars.clone();
+                lookahead.next();
+                if lookahead.next() == Some('<')
+                    && !matches!(lookahead.next(), Some('=') | Some('!'))
+                {
+                    count += 1;
+                    named = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    (count, named)
+}
+
+/// Returns the character `c` is compared as by case insensitive matching
+/// without the `u` flag: its `toUpperCase`, unless that's not a single
+/// UTF-16 code unit or turns a non-ASCII character into an ASCII one.
+fn canonicalize(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) if u.len_utf16() == 1 && (c.is_ascii() || !u.is_ascii()) => u,
+        _ => c,
+    }
+}
+
+thread_local! {
+    /// The characters of the basic multilingual plane that are equal to
+    /// other characters once canonicalized, grouped by their canonical
+    /// character. The other planes are matched by surrogates, which never
+    /// change.
+    static CASE_GROUPS: Vec<Vec<char>> = {
+        let mut groups: BTreeMap<char, Vec<char>> = BTreeMap::new();
+        for c in (0..=0xFFFF).filter_map(char::from_u32) {
+            groups.entry(canonicalize(c)).or_default().push(c);
+        }
+        groups.into_values().filter(|group| group.len() > 1).collect()
+    };
+}
+
+/// Adds to `class` the characters equal to one of its characters once
+/// canonicalized.
+fn fold_case(class: &mut ClassUnicode) {
+    let contains = |class: &ClassUnicode, c: char| {
+        class
+            .ranges()
+            .binary_search_by(|range| {
+                if range.end() < c {
+                    Ordering::Less
+                } else if range.start() > c {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            })
+            .is_ok()
+    };
+    let folded = CASE_GROUPS.with(|groups| {
+        let chars = groups
+            .iter()
+            .filter(|group| group.iter().any(|c| contains(class, *c)))
+            .flatten();
+        ClassUnicode::new(chars.map(|c| ClassUnicodeRange::new(*c, *c)))
+    });
+    class.union(&folded);
+}
+
+fn is_syntax_character(c: char) -> bool {
+    "^$\\.*+?()[]{}|".contains(c)
+}
+
+/// Writes `c` as a literal in the rust syntax, outside of a character class
+/// when `class` is false.
+fn write_rust_char(out: &mut String, c: char, class: bool) {
+    // these only have a meaning inside of a class (or with the `x` flag)
+    let class_only = "-&~#".contains(c);
+    if regex_syntax::is_meta_character(c) && (class || !class_only) {
+        out.push('\\');
+        out.push(c);
+    } else if c.is_control() || (c.is_whitespace() && c != ' ') {
+        let _ = write!(out, "\\x{{{:X}}}", c as u32);
+    } else {
+        out.push(c);
+    }
+}
+
+/// Writes `class` as a single atom in the rust syntax.
+fn write_rust_class(out: &mut String, class: &ClassUnicode) {
+    match class.ranges() {
+        [] => out.push_str(r"[^\x00-\x{10FFFF}]"),
+        [range] if range.start() == range.end() => write_rust_char(out, range.start(), false),
+        ranges => {
+            out.push('[');
+            for range in ranges {
+                write_rust_char(out, range.start(), true);
+                if range.end() != range.start() {
+                    out.push('-');
+                    write_rust_char(out, range.end(), true);
+                }
+            }
+            out.push(']');
+        }
+    }
+}
+
+fn write_class_atom(out: &mut String, atom: ClassAtom) {
+    match atom {
+        ClassAtom::Char(c) => write_rust_char(out, c, true),
+        ClassAtom::Set(set) => out.push_str(&set),
+    }
+}
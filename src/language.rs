@@ -0,0 +1,229 @@
+use regex_automata::util::primitives::StateID;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use wasm_bindgen::prelude::*;
+
+use crate::dfa;
+use crate::dfa::{Dfa, Product};
+
+#[wasm_bindgen(typescript_custom_section)]
+const LANGUAGE_CHECK_TYPE: &'static str = r#"/**
+ * The result of comparing the strings matched by two patterns, see
+ * `RRegex.isSubsetOf`, `RRegex.isEquivalent` and `RRegex.intersects`.
+ */
+export type LanguageCheck = {
+  /** The answer to the question asked. */
+  result: boolean
+
+  /**
+   * The shortest string that proves the answer, when there is one: a
+   * string only matched by one of the patterns when they are not a subset
+   * or not equivalent, or a string matched by both when they intersect.
+   * Shorter strings (in characters) and then smaller code points are
+   * preferred.
+   */
+  example?: string
+}
+
+/**
+ * Options accepted by `RRegex.isSubsetOf`, `RRegex.isEquivalent` and
+ * `RRegex.intersects`.
+ */
+export type LanguageOptions = {
+  /**
+   * Compares the strings matched as a whole, as if both patterns were
+   * wrapped in `^(?:...)$`, instead of the strings matched somewhere.
+   * Defaults to `false`.
+   */
+  whole?: boolean
+}
+
+/**
+ * How a pattern of a `RRegexSet` relates to the patterns before it, see
+ * `RRegexSet.analyze`.
+ */
+export type PatternAnalysis = {
+  /** The index of the pattern in the set. */
+  index: number
+
+  /** The pattern itself. */
+  pattern: string
+
+  /**
+   * The earlier patterns that match every text this pattern matches, so
+   * they always win over it. `example` is the shortest text matched by
+   * this pattern.
+   */
+  shadowedBy: PatternWitness[]
+
+  /**
+   * The earlier patterns that match some, but not all, of the texts this
+   * pattern matches. `example` is the shortest text matched by both.
+   */
+  overlapsWith: PatternWitness[]
+}
+
+/**
+ * An earlier pattern related to an analyzed pattern, with a text that
+ * proves it.
+ */
+export type PatternWitness = {
+  /** The index of the earlier pattern in the set. */
+  index: number
+
+  /** The shortest text matched by both patterns. */
+  example: string
+}"#;
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct LanguageOptions {
+    pub whole: bool,
+}
+
+#[derive(Serialize)]
+pub struct LanguageCheck {
+    result: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    example: Option<String>,
+}
+
+/// Whether every string accepted by `a` is accepted by `b`, with the
+/// shortest string only accepted by `a` otherwise.
+pub fn is_subset(a: &Dfa, b: &Dfa) -> LanguageCheck {
+    let example = shortest(
+        a,
+        b,
+        |a_live, _| a_live,
+        |a_accepts, b_accepts| a_accepts && !b_accepts,
+    );
+    LanguageCheck {
+        result: example.is_none(),
+        example,
+    }
+}
+
+/// Whether `a` and `b` accept the same strings, with the shortest string
+/// accepted by only one of them otherwise.
+pub fn is_equivalent(a: &Dfa, b: &Dfa) -> LanguageCheck {
+    let example = shortest(
+        a,
+        b,
+        |a_live, b_live| a_live || b_live,
+        |a_accepts, b_accepts| a_accepts != b_accepts,
+    );
+    LanguageCheck {
+        result: example.is_none(),
+        example,
+    }
+}
+
+/// Whether some string is accepted by both `a` and `b`, with the shortest
+/// one if so.
+pub fn intersects(a: &Dfa, b: &Dfa) -> LanguageCheck {
+    let example = shortest(
+        a,
+        b,
+        |a_live, b_live| a_live && b_live,
+        |a_accepts, b_accepts| a_accepts && b_accepts,
+    );
+    LanguageCheck {
+        result: example.is_some(),
+        example,
+    }
+}
+
+/// Searches the product of the DFAs of `a` and `b` breadth first, for the
+/// shortest string whose pair of states is `found`.
+///
+/// Pairs are explored by reading characters in ascending order, and each
+/// pair is only visited by the first (smallest) string that reaches it, so
+/// the first string found is also the smallest of its length.
+fn shortest<K, F>(a: &Dfa, b: &Dfa, keep: K, found: F) -> Option<String>
+where
+    K: Fn(bool, bool) -> bool + Copy,
+    F: Fn(bool, bool) -> bool,
+{
+    let product = Product::new(a, b);
+    let start = (dfa::start(a), dfa::start(b));
+
+    let mut visited: HashSet<(StateID, StateID)> = HashSet::new();
+    let mut paths: Vec<(Option<usize>, Vec<u8>)> = Vec::new();
+    let mut queue = VecDeque::new();
+    visited.insert(start);
+    queue.push_back((start, None));
+    while let Some(((a_state, b_state), path)) = queue.pop_front() {
+        if found(dfa::is_accepting(a, a_state), dfa::is_accepting(b, b_state)) {
+            return Some(string_of(&paths, path));
+        }
+        for (bytes, a_next, b_next) in product.successors(a_state, b_state, keep) {
+            if visited.insert((a_next, b_next)) {
+                paths.push((path, bytes));
+                queue.push_back(((a_next, b_next), Some(paths.len() - 1)));
+            }
+        }
+    }
+    None
+}
+
+/// Rebuilds the string of a path, stored as the last character and the
+/// index of the path before it.
+fn string_of(paths: &[(Option<usize>, Vec<u8>)], path: Option<usize>) -> String {
+    let mut chars = Vec::new();
+    let mut current = path;
+    while let Some(index) = current {
+        let (parent, bytes) = &paths[index];
+        chars.push(bytes.as_slice());
+        current = *parent;
+    }
+    let bytes: Vec<u8> = chars.into_iter().rev().flatten().copied().collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternAnalysis<'p> {
+    index: usize,
+    pattern: &'p str,
+    shadowed_by: Vec<PatternWitness>,
+    overlaps_with: Vec<PatternWitness>,
+}
+
+#[derive(Serialize)]
+pub struct PatternWitness {
+    index: usize,
+    example: String,
+}
+
+/// Compares every pattern with the patterns before it, given the DFAs that
+/// accept the texts each pattern matches.
+pub fn analyze<'p>(patterns: &'p [String], dfas: &[Dfa]) -> Vec<PatternAnalysis<'p>> {
+    let mut analyses = Vec::with_capacity(patterns.len());
+    for (index, pattern) in patterns.iter().enumerate() {
+        let mut shadowed_by = Vec::new();
+        let mut overlaps_with = Vec::new();
+        for earlier in 0..index {
+            // patterns that never match together can't shadow each other
+            let example = match intersects(&dfas[index], &dfas[earlier]).example {
+                Some(example) => example,
+                None => continue,
+            };
+            let witness = PatternWitness {
+                index: earlier,
+                example,
+            };
+            if is_subset(&dfas[index], &dfas[earlier]).result {
+                shadowed_by.push(witness);
+            } else {
+                overlaps_with.push(witness);
+            }
+        }
+        analyses.push(PatternAnalysis {
+            index,
+            pattern,
+            shadowed_by,
+            overlaps_with,
+        });
+    }
+    analyses
+}
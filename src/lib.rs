@@ -8,6 +8,36 @@ mod types;
 mod rregex;
 mod rregexset;
 mod escape;
+mod error;
+mod jsregexp;
+mod literals;
+mod options;
+mod glob;
+mod rglob;
+mod rglobset;
+mod rignore;
+mod posix;
+mod sql;
+mod explain;
+mod dfa;
+mod dot;
+mod generate;
+mod language;
+mod rmatcher;
+mod engine;
+mod profile;
+mod cache;
+mod descriptor;
+mod template;
+mod trie;
+mod rliteralset;
+mod grok;
+mod rgrok;
+mod presets;
+mod secrets;
+mod rsecretscanner;
+mod rlexer;
+mod rscanner;
 
 #[allow(dead_code)]
 pub fn set_panic_hook() {
@@ -0,0 +1,204 @@
+use regex_syntax::hir::literal::{ExtractKind, Extractor, Seq};
+use regex_syntax::Parser;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::types::Bytes;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+#[wasm_bindgen(typescript_custom_section)]
+const LITERALS_OPTIONS_TYPE: &'static str = r#"/**
+ * Options accepted by `RRegex.literals` and `RRegexSet.literals`.
+ *
+ * Every limit maps to the option with the same name on
+ * `regex_syntax::hir::literal::Extractor`, when omitted the defaults of the
+ * extractor are used.
+ *
+ * @see https://docs.rs/regex-syntax/latest/regex_syntax/hir/literal/struct.Extractor.html
+ */
+export type LiteralsOptions = {
+  /**
+   * Whether to extract the literals every match must start with (`prefix`)
+   * or end with (`suffix`). Defaults to `prefix`.
+   */
+  kind?: 'prefix' | 'suffix'
+
+  /**
+   * The maximum number of literals in the sequence. When the limit is
+   * exceeded the literals are trimmed (and become inexact) to fit, and if
+   * that isn't enough the sequence becomes infinite.
+   */
+  limit?: number
+
+  /**
+   * The maximum size of a character class that is expanded into literals.
+   */
+  limitClass?: number
+
+  /**
+   * The maximum number of times a repetition is expanded.
+   */
+  limitRepeat?: number
+
+  /**
+   * The maximum length, in bytes, of every literal. Longer literals are
+   * truncated and become inexact.
+   */
+  limitLiteralLen?: number
+}"#;
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum LiteralsKind {
+    #[default]
+    Prefix,
+    Suffix,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LiteralsOptions {
+    #[serde(default)]
+    kind: LiteralsKind,
+    limit: Option<usize>,
+    limit_class: Option<usize>,
+    limit_repeat: Option<usize>,
+    limit_literal_len: Option<usize>,
+}
+
+impl LiteralsOptions {
+    fn extractor(&self) -> Extractor {
+        let mut extractor = Extractor::new();
+        extractor.kind(match self.kind {
+            LiteralsKind::Prefix => ExtractKind::Prefix,
+            LiteralsKind::Suffix => ExtractKind::Suffix,
+        });
+        if let Some(limit) = self.limit {
+            extractor.limit_total(limit);
+        }
+        if let Some(limit) = self.limit_class {
+            extractor.limit_class(limit);
+        }
+        if let Some(limit) = self.limit_repeat {
+            extractor.limit_repeat(limit);
+        }
+        if let Some(limit) = self.limit_literal_len {
+            extractor.limit_literal_len(limit);
+        }
+        extractor
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const LITERAL_SEQ_TYPE: &'static str = r#"/**
+ * A sequence of literals extracted from a regular expression.
+ *
+ * When the sequence is finite, every match of the regular expression starts
+ * (or ends, for suffixes) with at least one of its literals. An infinite
+ * sequence means that no useful set of literals could be extracted within the
+ * configured limits, so it can't be used as a pre-filter.
+ *
+ * ```typescript
+ * import { RRegex } from "rregex"
+ *
+ * const re = new RRegex("(foo|bar)baz+")
+ * const seq = re.literals({ kind: "prefix" })
+ * expect(seq.literals.map(lit => lit.value)).toEqual(["foobaz", "barbaz"])
+ * expect(seq.literals.every(lit => lit.exact)).toBe(false)
+ * ```
+ *
+ * @see https://docs.rs/regex-syntax/latest/regex_syntax/hir/literal/struct.Seq.html
+ */
+export type LiteralSeq = {
+  /** Whether the literals are prefixes or suffixes of every match. */
+  kind: 'prefix' | 'suffix'
+
+  /**
+   * `false` when the sequence is infinite, in which case `literals` is empty
+   * and no literal pre-filter can be built.
+   */
+  finite: boolean
+
+  /**
+   * `true` when the sequence is finite and every literal in it is exact.
+   * That is, the regular expression matches exactly the literals and nothing
+   * else.
+   */
+  exact: boolean
+
+  /** The literals in the sequence, in order of preference. */
+  literals: ExtractedLiteral[]
+}
+
+/**
+ * A single literal in a `LiteralSeq`.
+ */
+export type ExtractedLiteral = {
+  /**
+   * The literal decoded as UTF-8. Invalid sequences (e.g. a literal truncated
+   * by `limitLiteralLen` in the middle of a code point) are replaced with
+   * `U+FFFD`, use `bytes` when the exact value is required.
+   */
+  value: string
+
+  /** The raw bytes of the literal. */
+  bytes: Uint8Array
+
+  /**
+   * An exact literal is a complete match of the regular expression, an
+   * inexact literal is only the prefix (or suffix) of a match.
+   */
+  exact: boolean
+}"#;
+
+#[derive(Serialize)]
+pub struct LiteralSeq<'s> {
+    kind: &'static str,
+    finite: bool,
+    exact: bool,
+    literals: Vec<ExtractedLiteral<'s>>,
+}
+
+#[derive(Serialize)]
+pub struct ExtractedLiteral<'s> {
+    value: String,
+    bytes: Bytes<'s>,
+    exact: bool,
+}
+
+impl<'s> LiteralSeq<'s> {
+    fn new(kind: LiteralsKind, seq: &'s Seq) -> Self {
+        let literals: Vec<ExtractedLiteral> = seq
+            .literals()
+            .unwrap_or(&[])
+            .iter()
+            .map(|lit| ExtractedLiteral {
+                value: String::from_utf8_lossy(lit.as_bytes()).into_owned(),
+                bytes: Bytes(lit.as_bytes()),
+                exact: lit.is_exact(),
+            })
+            .collect();
+
+        LiteralSeq {
+            kind: match kind {
+                LiteralsKind::Prefix => "prefix",
+                LiteralsKind::Suffix => "suffix",
+            },
+            finite: seq.is_finite(),
+            exact: seq.is_finite() && seq.is_exact(),
+            literals,
+        }
+    }
+}
+
+/// Extracts the literal sequence of `pattern` and serializes it as a
+/// `LiteralSeq`.
+pub fn extract(parser: &mut Parser, pattern: &str, options: &LiteralsOptions) -> Result<JsValue> {
+    let hir = parser
+        .parse(pattern)
+        .map_err(serde_wasm_bindgen::Error::new)?;
+
+    let seq = options.extractor().extract(&hir);
+    serde_wasm_bindgen::to_value(&LiteralSeq::new(options.kind, &seq))
+}
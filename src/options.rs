@@ -0,0 +1,253 @@
+use regex::{RegexBuilder, RegexSetBuilder};
+use regex_syntax::ast;
+use regex_syntax::{Parser, ParserBuilder};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const REGEX_OPTIONS_TYPE: &'static str = r#"/**
+ * Options used to compile a regular expression, every option maps to the
+ * method with the same name on `regex::RegexBuilder`.
+ *
+ * Flags set in the pattern (e.g. `(?i)`) always take precedence over these
+ * options.
+ *
+ * ```typescript
+ * import { RRegex } from "rregex"
+ *
+ * const re = new RRegex("^foo$", { caseInsensitive: true, multiLine: true })
+ * expect(re.isMatch("bar\nFOO\nbaz")).toBe(true)
+ * ```
+ *
+ * @see https://docs.rs/regex/latest/regex/struct.RegexBuilder.html
+ */
+export type RegexOptions = {
+  /** Enables case insensitive matching, like the `i` flag. Defaults to `false`. */
+  caseInsensitive?: boolean
+
+  /**
+   * Enables multi-line mode, where `^` and `$` match at the beginning and
+   * end of lines, like the `m` flag. Defaults to `false`.
+   */
+  multiLine?: boolean
+
+  /** Allows `.` to match `\n`, like the `s` flag. Defaults to `false`. */
+  dotMatchesNewLine?: boolean
+
+  /**
+   * Treats `\r\n` as a line terminator for `^`, `$` and `.`, like the `R`
+   * flag. Defaults to `false`.
+   */
+  crlf?: boolean
+
+  /** Swaps the meaning of greedy and lazy repetitions, like the `U` flag. Defaults to `false`. */
+  swapGreed?: boolean
+
+  /** Ignores whitespace and allows `#` comments, like the `x` flag. Defaults to `false`. */
+  ignoreWhitespace?: boolean
+
+  /** Enables Unicode mode, like the `u` flag. Defaults to `true`. */
+  unicode?: boolean
+
+  /** Enables octal escapes like `\141`. Defaults to `false`. */
+  octal?: boolean
+
+  /** The approximate size limit, in bytes, of the compiled regular expression. */
+  sizeLimit?: number
+
+  /**
+   * The approximate size limit, in bytes, of the cache used by the lazy DFA,
+   * and of the DFAs built to compare patterns, e.g. by `RRegex.isSubsetOf`,
+   * which default to 10 MB.
+   */
+  dfaSizeLimit?: number
+
+  /** The nesting limit of the pattern. */
+  nestLimit?: number
+
+  /**
+   * Forces the `regex-automata` engine used by `RRegex` to search, instead
+   * of letting the regex crate pick one for every search (`auto`, the
+   * default). See `RegexEngine` for the trade-offs, and `RRegex.info` for
+   * the engines that were built. `RRegexSet` ignores this option.
+   */
+  engine?: RegexEngine
+
+  /**
+   * Records the calls of every search method, see `RRegex.stats`. Defaults
+   * to `false`.
+   */
+  profile?: boolean
+}
+
+/**
+ * An engine of `regex-automata`:
+ *
+ * - `auto` lets the regex crate combine its engines, picking the fastest
+ *   one for every search.
+ * - `pikevm` simulates the NFA, it supports every pattern and haystack but
+ *   is the slowest engine.
+ * - `backtrack` is a bounded backtracker, faster than the PikeVM but it
+ *   throws on haystacks longer than a limit that depends on the size of the
+ *   NFA.
+ * - `onepass` is a one-pass DFA, the fastest engine to resolve capture
+ *   groups, only for patterns anchored at the start (with `^` or `\A`) that
+ *   are never ambiguous about which group matches.
+ * - `hybrid` is a lazy DFA, built while searching, which finds the bounds
+ *   of matches, capture groups are resolved with the PikeVM.
+ * - `dfa` is a full dense DFA, built ahead of time, which may be huge for
+ *   some patterns, capture groups are resolved with the PikeVM.
+ *
+ * DFAs only support Unicode word boundaries on ASCII haystacks, a search
+ * that finds a non-ASCII character is done again with the PikeVM, use
+ * `(?-u:\b)` to avoid it.
+ *
+ * @see https://docs.rs/regex-automata/latest/regex_automata/#available-regex-engines
+ */
+export type RegexEngine = 'auto' | 'pikevm' | 'backtrack' | 'onepass' | 'hybrid' | 'dfa'"#;
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RegexOptions {
+    pub case_insensitive: bool,
+    pub multi_line: bool,
+    pub dot_matches_new_line: bool,
+    pub crlf: bool,
+    pub swap_greed: bool,
+    pub ignore_whitespace: bool,
+    pub unicode: bool,
+    pub octal: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dfa_size_limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nest_limit: Option<u32>,
+    #[serde(skip_serializing_if = "RegexEngine::is_auto")]
+    pub engine: RegexEngine,
+    pub profile: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RegexEngine {
+    #[default]
+    Auto,
+    PikeVm,
+    Backtrack,
+    OnePass,
+    Hybrid,
+    Dfa,
+}
+
+impl RegexEngine {
+    pub fn is_auto(&self) -> bool {
+        *self == RegexEngine::Auto
+    }
+}
+
+impl Default for RegexOptions {
+    fn default() -> Self {
+        RegexOptions {
+            case_insensitive: false,
+            multi_line: false,
+            dot_matches_new_line: false,
+            crlf: false,
+            swap_greed: false,
+            ignore_whitespace: false,
+            unicode: true,
+            octal: false,
+            size_limit: None,
+            dfa_size_limit: None,
+            nest_limit: None,
+            engine: RegexEngine::Auto,
+            profile: false,
+        }
+    }
+}
+
+impl RegexOptions {
+    /// Returns a builder for `pattern` configured with these options.
+    pub fn regex_builder(&self, pattern: &str) -> RegexBuilder {
+        let mut builder = RegexBuilder::new(pattern);
+        builder
+            .case_insensitive(self.case_insensitive)
+            .multi_line(self.multi_line)
+            .dot_matches_new_line(self.dot_matches_new_line)
+            .crlf(self.crlf)
+            .swap_greed(self.swap_greed)
+            .ignore_whitespace(self.ignore_whitespace)
+            .unicode(self.unicode)
+            .octal(self.octal);
+        if let Some(limit) = self.size_limit {
+            builder.size_limit(limit);
+        }
+        if let Some(limit) = self.dfa_size_limit {
+            builder.dfa_size_limit(limit);
+        }
+        if let Some(limit) = self.nest_limit {
+            builder.nest_limit(limit);
+        }
+        builder
+    }
+
+    /// Returns a builder for `patterns` configured with these options.
+    pub fn regex_set_builder<I, S>(&self, patterns: I) -> RegexSetBuilder
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut builder = RegexSetBuilder::new(patterns);
+        builder
+            .case_insensitive(self.case_insensitive)
+            .multi_line(self.multi_line)
+            .dot_matches_new_line(self.dot_matches_new_line)
+            .crlf(self.crlf)
+            .swap_greed(self.swap_greed)
+            .ignore_whitespace(self.ignore_whitespace)
+            .unicode(self.unicode)
+            .octal(self.octal);
+        if let Some(limit) = self.size_limit {
+            builder.size_limit(limit);
+        }
+        if let Some(limit) = self.dfa_size_limit {
+            builder.dfa_size_limit(limit);
+        }
+        if let Some(limit) = self.nest_limit {
+            builder.nest_limit(limit);
+        }
+        builder
+    }
+
+    /// Returns a parser that produces the same high level intermediate
+    /// representation the compiled regular expression uses.
+    pub fn parser(&self) -> Parser {
+        let mut builder = ParserBuilder::new();
+        builder
+            .case_insensitive(self.case_insensitive)
+            .multi_line(self.multi_line)
+            .dot_matches_new_line(self.dot_matches_new_line)
+            .crlf(self.crlf)
+            .swap_greed(self.swap_greed)
+            .ignore_whitespace(self.ignore_whitespace)
+            .unicode(self.unicode)
+            .octal(self.octal);
+        if let Some(limit) = self.nest_limit {
+            builder.nest_limit(limit);
+        }
+        builder.build()
+    }
+
+    /// Returns a parser that produces the abstract syntax tree of a pattern,
+    /// before flags like `caseInsensitive` are applied.
+    pub fn ast_parser(&self) -> ast::parse::Parser {
+        let mut builder = ast::parse::ParserBuilder::new();
+        builder
+            .ignore_whitespace(self.ignore_whitespace)
+            .octal(self.octal);
+        if let Some(limit) = self.nest_limit {
+            builder.nest_limit(limit);
+        }
+        builder.build()
+    }
+}
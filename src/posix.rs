@@ -0,0 +1,426 @@
+use regex_syntax::hir::{
+    Capture, Class, ClassUnicode, ClassUnicodeRange, Dot, Hir, Look, Repetition,
+};
+
+use crate::error::PatternError;
+
+/// The maximum count of an interval like `{m,n}`, `RE_DUP_MAX` in POSIX.
+const DUP_MAX: u32 = 255;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Flavor {
+    /// POSIX basic regular expressions, as used by `grep` and `sed`.
+    Basic,
+    /// POSIX extended regular expressions, as used by `grep -E` and `awk`.
+    Extended,
+    /// The SQL `SIMILAR TO` syntax, with the given escape character.
+    Similar(Option<char>),
+}
+
+/// Translates a POSIX regular expression (or a SQL `SIMILAR TO` pattern)
+/// into a high level intermediate representation.
+pub fn parse(pattern: &str, flavor: Flavor) -> Result<Hir, PatternError> {
+    let mut parser = PosixParser {
+        pattern,
+        chars: pattern.char_indices().collect(),
+        index: 0,
+        flavor,
+        captures: 0,
+    };
+    let hir = parser.alternation(0)?;
+
+    Ok(match flavor {
+        // `SIMILAR TO` always matches the whole string
+        Flavor::Similar(_) => Hir::concat(vec![Hir::look(Look::Start), hir, Hir::look(Look::End)]),
+        _ => hir,
+    })
+}
+
+/// The characters of a class like `[:alpha:]`, in the POSIX locale.
+fn named_class(name: &str) -> Option<&'static [(char, char)]> {
+    let ranges: &'static [(char, char)] = match name {
+        "alnum" => &[('0', '9'), ('A', 'Z'), ('a', 'z')],
+        "alpha" => &[('A', 'Z'), ('a', 'z')],
+        "blank" => &[('\t', '\t'), (' ', ' ')],
+        "cntrl" => &[('\0', '\x1F'), ('\x7F', '\x7F')],
+        "digit" => &[('0', '9')],
+        "graph" => &[('!', '~')],
+        "lower" => &[('a', 'z')],
+        "print" => &[(' ', '~')],
+        "punct" => &[('!', '/'), (':', '@'), ('[', '`'), ('{', '~')],
+        "space" => &[('\t', '\r'), (' ', ' ')],
+        "upper" => &[('A', 'Z')],
+        "xdigit" => &[('0', '9'), ('A', 'F'), ('a', 'f')],
+        _ => return None,
+    };
+    Some(ranges)
+}
+
+struct PosixParser<'p> {
+    pattern: &'p str,
+    chars: Vec<(usize, char)>,
+    index: usize,
+    flavor: Flavor,
+    captures: u32,
+}
+
+impl<'p> PosixParser<'p> {
+    fn peek(&self) -> Option<char> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, ahead: usize) -> Option<char> {
+        self.chars.get(self.index + ahead).map(|(_, c)| *c)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.index += 1;
+        }
+        c
+    }
+
+    fn offset_of(&self, index: usize) -> usize {
+        self.chars
+            .get(index)
+            .map(|(offset, _)| *offset)
+            .unwrap_or(self.pattern.len())
+    }
+
+    fn syntax<M: ToString>(&self, message: M, start: usize) -> PatternError {
+        PatternError::syntax(
+            message,
+            self.pattern,
+            self.offset_of(start),
+            self.offset_of(self.index),
+        )
+    }
+
+    fn unsupported<M: ToString>(&self, message: M, start: usize) -> PatternError {
+        PatternError::unsupported(
+            message,
+            self.pattern,
+            self.offset_of(start),
+            self.offset_of(self.index),
+        )
+    }
+
+    /// The character that escapes special characters.
+    fn escape(&self) -> Option<char> {
+        match self.flavor {
+            Flavor::Similar(escape) => escape,
+            _ => Some('\\'),
+        }
+    }
+
+    fn is_escaped(&self, c: char) -> bool {
+        self.peek() == self.escape() && self.peek_at(1) == Some(c)
+    }
+
+    /// Whether the current position closes the group at `depth`.
+    fn at_group_end(&self, depth: usize) -> bool {
+        depth > 0
+            && match self.flavor {
+                Flavor::Basic => self.is_escaped(')'),
+                _ => self.peek() == Some(')'),
+            }
+    }
+
+    fn at_alternation(&self) -> bool {
+        self.flavor != Flavor::Basic && self.peek() == Some('|')
+    }
+
+    fn alternation(&mut self, depth: usize) -> Result<Hir, PatternError> {
+        let mut branches = vec![self.branch(depth)?];
+        while self.at_alternation() {
+            self.bump();
+            branches.push(self.branch(depth)?);
+        }
+        Ok(Hir::alternation(branches))
+    }
+
+    fn branch(&mut self, depth: usize) -> Result<Hir, PatternError> {
+        let mut items = Vec::new();
+        let branch_start = self.index;
+        while self.peek().is_some() && !self.at_alternation() && !self.at_group_end(depth) {
+            let (atom, quantifiable) = self.atom(depth, branch_start)?;
+            let atom = if quantifiable {
+                self.quantifiers(atom)?
+            } else {
+                atom
+            };
+            items.push(atom);
+        }
+        Ok(Hir::concat(items))
+    }
+
+    /// Parses a single atom, returns whether it can be repeated.
+    fn atom(&mut self, depth: usize, branch_start: usize) -> Result<(Hir, bool), PatternError> {
+        let start = self.index;
+        let basic = self.flavor == Flavor::Basic;
+        let similar = matches!(self.flavor, Flavor::Similar(_));
+        let c = self.bump().unwrap_or_default();
+
+        if Some(c) == self.escape() {
+            return self.escaped(depth, start);
+        }
+        let atom = match c {
+            '[' => Hir::class(Class::Unicode(self.bracket(start)?)),
+            '(' if !basic => return self.group(depth, start).map(|hir| (hir, true)),
+            ')' if !basic => return Err(self.syntax("unmatched ')'", start)),
+            '.' if !similar => Hir::dot(Dot::AnyChar),
+            '%' if similar => Hir::repetition(Repetition {
+                min: 0,
+                max: None,
+                greedy: true,
+                sub: Box::new(Hir::dot(Dot::AnyChar)),
+            }),
+            '_' if similar => Hir::dot(Dot::AnyChar),
+            // in a basic expression, `^` is an anchor only at the start of
+            // a branch and `$` only at its end
+            '^' if !similar && (!basic || start == branch_start) => {
+                return Ok((Hir::look(Look::Start), false));
+            }
+            '$' if !similar && (!basic || self.peek().is_none() || self.at_group_end(depth)) => {
+                return Ok((Hir::look(Look::End), false));
+            }
+            // a leading `*` is a literal in a basic expression
+            '*' if basic => Hir::literal(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            '*' | '+' | '?' if !basic => return Err(self.syntax("nothing to repeat", start)),
+            '{' if !basic => return Err(self.syntax("nothing to repeat", start)),
+            c => Hir::literal(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        };
+        Ok((atom, true))
+    }
+
+    /// Parses an escape, after the escape character.
+    fn escaped(&mut self, depth: usize, start: usize) -> Result<(Hir, bool), PatternError> {
+        let c = match self.bump() {
+            Some(c) => c,
+            None => return Err(self.syntax("trailing escape character", start)),
+        };
+        if self.flavor == Flavor::Basic {
+            match c {
+                '(' => return self.group(depth, start).map(|hir| (hir, true)),
+                ')' => return Err(self.syntax("unmatched '\\)'", start)),
+                '{' => return Err(self.syntax("nothing to repeat", start)),
+                _ => {}
+            }
+        }
+        if !matches!(self.flavor, Flavor::Similar(_)) {
+            if c.is_ascii_digit() && c != '0' {
+                return Err(self.unsupported("backreferences are not supported", start));
+            }
+            // escaping an ordinary character is undefined, and GNU gives
+            // most of them a meaning, like `\w` or `\|`
+            if c.is_alphanumeric()
+                || "<>`'".contains(c)
+                || ("+?|".contains(c) && self.flavor == Flavor::Basic)
+            {
+                return Err(
+                    self.unsupported(format!("'\\{}' is not part of the POSIX syntax", c), start)
+                );
+            }
+        }
+        Ok((Hir::literal(c.encode_utf8(&mut [0; 4]).as_bytes()), true))
+    }
+
+    /// Parses a group, after its opening parenthesis.
+    fn group(&mut self, depth: usize, start: usize) -> Result<Hir, PatternError> {
+        self.captures += 1;
+        let index = self.captures;
+        let sub = self.alternation(depth + 1)?;
+        if !self.at_group_end(depth + 1) {
+            return Err(self.syntax("unclosed group", start));
+        }
+        if self.flavor == Flavor::Basic {
+            self.bump();
+        }
+        self.bump();
+
+        Ok(Hir::capture(Capture {
+            index,
+            name: None,
+            sub: Box::new(sub),
+        }))
+    }
+
+    /// Parses the quantifiers that follow an atom.
+    fn quantifiers(&mut self, atom: Hir) -> Result<Hir, PatternError> {
+        let mut atom = atom;
+        loop {
+            let basic = self.flavor == Flavor::Basic;
+            let (min, max) = match self.peek() {
+                Some('*') => {
+                    self.bump();
+                    (0, None)
+                }
+                Some('+') if !basic => {
+                    self.bump();
+                    (1, None)
+                }
+                Some('?') if !basic => {
+                    self.bump();
+                    (0, Some(1))
+                }
+                Some('{') if !basic => {
+                    self.bump();
+                    self.interval()?
+                }
+                _ if basic && self.is_escaped('{') => {
+                    self.index += 2;
+                    self.interval()?
+                }
+                _ => return Ok(atom),
+            };
+            atom = Hir::repetition(Repetition {
+                min,
+                max,
+                greedy: true,
+                sub: Box::new(atom),
+            });
+        }
+    }
+
+    /// Parses the bounds of an interval like `{m,n}`, after its `{`.
+    fn interval(&mut self) -> Result<(u32, Option<u32>), PatternError> {
+        let interval_start = self.index - if self.flavor == Flavor::Basic { 2 } else { 1 };
+        let min = match self.number() {
+            Some(min) => min,
+            None => return Err(self.syntax("invalid interval", interval_start)),
+        };
+        let max = if self.peek() == Some(',') {
+            self.bump();
+            self.number()
+        } else {
+            Some(min)
+        };
+
+        let closed = if self.flavor == Flavor::Basic {
+            self.is_escaped('}')
+        } else {
+            self.peek() == Some('}')
+        };
+        if !closed {
+            return Err(self.syntax("unclosed interval", interval_start));
+        }
+        self.index += if self.flavor == Flavor::Basic { 2 } else { 1 };
+
+        if min > DUP_MAX || max.is_some_and(|max| max > DUP_MAX) {
+            return Err(self.syntax(
+                format!("the count of an interval can't exceed {}", DUP_MAX),
+                interval_start,
+            ));
+        }
+        if max.is_some_and(|max| max < min) {
+            return Err(self.syntax("invalid interval, min is greater than max", interval_start));
+        }
+        Ok((min, max))
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let mut value: Option<u32> = None;
+        while let Some(digit) = self.peek().and_then(|c| c.to_digit(10)) {
+            self.bump();
+            value = Some(value.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+        }
+        value
+    }
+
+    /// Parses a bracket expression, after its `[`.
+    fn bracket(&mut self, start: usize) -> Result<ClassUnicode, PatternError> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.bump();
+        }
+
+        let mut class = ClassUnicode::empty();
+        let mut first = true;
+        loop {
+            let item_start = self.index;
+            let c = match self.peek() {
+                None => {
+                    return Err(self.syntax("unclosed bracket expression", start));
+                }
+                Some(']') if !first => {
+                    self.bump();
+                    break;
+                }
+                Some('[') if self.peek_at(1) == Some(':') => {
+                    self.index += 2;
+                    let name = self.bracket_name(':', item_start)?;
+                    let ranges = named_class(&name).ok_or_else(|| {
+                        self.syntax(format!("unknown character class '{}'", name), item_start)
+                    })?;
+                    for (start, end) in ranges {
+                        class.push(ClassUnicodeRange::new(*start, *end));
+                    }
+                    first = false;
+                    continue;
+                }
+                Some(_) => self.bracket_char(item_start)?,
+            };
+            first = false;
+
+            let is_range = self.peek() == Some('-') && !matches!(self.peek_at(1), None | Some(']'));
+            if !is_range {
+                class.push(ClassUnicodeRange::new(c, c));
+                continue;
+            }
+            self.bump();
+            let end = self.bracket_char(self.index)?;
+            if end < c {
+                return Err(self.syntax("invalid range end", item_start));
+            }
+            class.push(ClassUnicodeRange::new(c, end));
+        }
+
+        if negated {
+            class.negate();
+        }
+        Ok(class)
+    }
+
+    /// Parses a single character of a bracket expression, including
+    /// collating symbols like `[.-.]` and equivalence classes like `[=a=]`.
+    fn bracket_char(&mut self, start: usize) -> Result<char, PatternError> {
+        match (self.peek(), self.peek_at(1)) {
+            (Some('['), Some(delimiter @ '.')) | (Some('['), Some(delimiter @ '=')) => {
+                self.index += 2;
+                let name = self.bracket_name(delimiter, start)?;
+                let mut chars = name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(c),
+                    _ => Err(self.unsupported(
+                        format!("multi-character collating element '{}'", name),
+                        start,
+                    )),
+                }
+            }
+            (Some(c), _) => {
+                self.bump();
+                Ok(c)
+            }
+            (None, _) => Err(self.syntax("unclosed bracket expression", start)),
+        }
+    }
+
+    /// Parses the name of a `[:class:]`, `[.symbol.]` or `[=equivalence=]`,
+    /// after its opening delimiter.
+    fn bracket_name(&mut self, delimiter: char, start: usize) -> Result<String, PatternError> {
+        let mut name = String::new();
+        loop {
+            match self.bump() {
+                Some(c) if c == delimiter && self.peek() == Some(']') => {
+                    self.bump();
+                    return Ok(name);
+                }
+                Some(c) => name.push(c),
+                None => {
+                    return Err(self.syntax(format!("unclosed '[{}'", delimiter), start));
+                }
+            }
+        }
+    }
+}
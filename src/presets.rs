@@ -0,0 +1,300 @@
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use wasm_bindgen::prelude::*;
+
+use crate::grok;
+use crate::options::RegexOptions;
+use crate::rregex::RRegex;
+use crate::types;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+#[wasm_bindgen(typescript_custom_section)]
+const PRESET_TYPES: &'static str = r#"/**
+ * The formats of `RPresets`, with the named groups they capture:
+ *
+ * - `email`: `local` and `domain`. Strict follows the dot-atom syntax of
+ *   RFC 5322 with a hostname as domain (no quoted local parts, no IP
+ *   literals), loose accepts anything around a `@` without spaces.
+ * - `ipv4`: `octet1` to `octet4`. Strict only accepts octets from `0` to
+ *   `255` without leading zeros, loose accepts any 1 to 3 digits.
+ * - `ipv6`: `address` and `zone` (as in `fe80::1%eth0`). Strict accepts
+ *   every compressed form of RFC 4291, including embedded IPv4 addresses,
+ *   loose accepts hexadecimal digits, `:` and `.` with at least two `:`.
+ * - `uuid`: `version` and `variant`. Strict only accepts versions `1` to
+ *   `8` of the RFC 9562 variant, loose accepts any hexadecimal digits.
+ * - `url`: `scheme`, `userinfo`, `host`, `port`, `path`, `query` and
+ *   `fragment`. Strict only accepts the characters of RFC 3986 (with
+ *   percent-encoding), hostnames, IPv4 or `[IPv6]` hosts and ports up to
+ *   `65535`, loose accepts anything but spaces in every component.
+ * - `semver`: `major`, `minor`, `patch`, `prerelease` and `buildmetadata`.
+ *   Strict is the regular expression of semver.org, loose accepts a `v`
+ *   prefix, leading zeros and a missing patch.
+ * - `iso8601`: `year`, `month`, `day`, `hour`, `minute`, `second`,
+ *   `fraction` and `offset`, for a date with an optional time. Strict uses
+ *   the extended format (`2024-05-01T10:20:30Z`) and checks the range of
+ *   each component, but not the number of days of each month, loose also
+ *   accepts the basic format (`20240501T102030Z`) and any two digits.
+ * - `creditCard`: `number`. Strict accepts the numbers of Visa,
+ *   Mastercard, American Express, Discover, Diners Club and JCB without
+ *   separators, loose accepts 13 to 19 digits separated by spaces or
+ *   dashes. Neither checks the Luhn checksum.
+ */
+export type PresetName = 'email' | 'ipv4' | 'ipv6' | 'uuid' | 'url' | 'semver' | 'iso8601' | 'creditCard'
+
+/**
+ * Options accepted by `RPresets.get` and `RPresets.pattern`.
+ */
+export type PresetOptions = {
+  /**
+   * `strict` only matches valid values of the format, `loose` matches
+   * anything shaped like them, to find candidates that are validated
+   * later. Defaults to `strict`.
+   */
+  strictness?: 'loose' | 'strict'
+
+  /**
+   * Only matches when the whole string is a value of the format, instead
+   * of finding values anywhere in it. Defaults to `false`.
+   */
+  anchored?: boolean
+}"#;
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Strictness {
+    Loose,
+    #[default]
+    Strict,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PresetOptions {
+    strictness: Strictness,
+    anchored: bool,
+}
+
+/// The names of the presets, in the order they are documented.
+const NAMES: &[&str] = &[
+    "email",
+    "ipv4",
+    "ipv6",
+    "uuid",
+    "url",
+    "semver",
+    "iso8601",
+    "creditCard",
+];
+
+/// A label of a hostname, and the last one, which can't be numeric.
+const LABEL: &str = r"[0-9A-Za-z](?:[0-9A-Za-z-]{0,61}[0-9A-Za-z])?";
+const TOP_LABEL: &str = r"[A-Za-z](?:[0-9A-Za-z-]{0,61}[0-9A-Za-z])?";
+
+const OCTET: &str = r"25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9]";
+
+/// Returns the pattern of the preset `name`, unanchored.
+fn source(name: &str, strictness: Strictness) -> Option<String> {
+    let strict = strictness == Strictness::Strict;
+    let pattern = match name {
+        "email" if strict => format!(
+            r"(?P<local>[A-Za-z0-9!#$%&'*+/=?^_`{{|}}~-]+(?:\.[A-Za-z0-9!#$%&'*+/=?^_`{{|}}~-]+)*)@(?P<domain>(?:{}\.)+{})",
+            LABEL, TOP_LABEL
+        ),
+        "email" => String::from(r"(?P<local>[^\s@]+)@(?P<domain>[^\s@]+)"),
+        "ipv4" if strict => format!(
+            r"(?P<octet1>{0})\.(?P<octet2>{0})\.(?P<octet3>{0})\.(?P<octet4>{0})",
+            OCTET
+        ),
+        "ipv4" => String::from(
+            r"(?P<octet1>[0-9]{1,3})\.(?P<octet2>[0-9]{1,3})\.(?P<octet3>[0-9]{1,3})\.(?P<octet4>[0-9]{1,3})",
+        ),
+        "ipv6" if strict => format!(r"(?P<address>{})(?:%(?P<zone>[0-9A-Za-z._~-]+))?", ipv6()),
+        "ipv6" => String::from(
+            r"(?P<address>[0-9A-Fa-f.]*:[0-9A-Fa-f.]*:[0-9A-Fa-f:.]*)(?:%(?P<zone>[0-9A-Za-z._~-]+))?",
+        ),
+        "uuid" if strict => String::from(
+            r"[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-(?P<version>[1-8])[0-9A-Fa-f]{3}-(?P<variant>[89ABab])[0-9A-Fa-f]{3}-[0-9A-Fa-f]{12}",
+        ),
+        "uuid" => String::from(
+            r"[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-(?P<version>[0-9A-Fa-f])[0-9A-Fa-f]{3}-(?P<variant>[0-9A-Fa-f])[0-9A-Fa-f]{3}-[0-9A-Fa-f]{12}",
+        ),
+        "url" if strict => {
+            let unreserved = r"A-Za-z0-9._~!$&'()*+,;=\-";
+            let encoded = r"%[0-9A-Fa-f]{2}";
+            format!(
+                concat!(
+                    r"(?P<scheme>[A-Za-z][A-Za-z0-9+.-]*)://",
+                    r"(?:(?P<userinfo>(?:[{u}:]|{e})*)@)?",
+                    r"(?P<host>(?:{label}\.)*{top}|{ipv4}|\[{ipv6}\])",
+                    r"(?::(?P<port>6553[0-5]|655[0-2][0-9]|65[0-4][0-9]{{2}}|6[0-4][0-9]{{3}}|[1-5][0-9]{{4}}|[1-9][0-9]{{0,3}}|0))?",
+                    r"(?P<path>(?:/(?:[{u}:@]|{e})*)*)",
+                    r"(?:\?(?P<query>(?:[{u}:@/?]|{e})*))?",
+                    r"(?:#(?P<fragment>(?:[{u}:@/?]|{e})*))?",
+                ),
+                u = unreserved,
+                e = encoded,
+                label = LABEL,
+                top = TOP_LABEL,
+                ipv4 = ipv4(),
+                ipv6 = ipv6(),
+            )
+        }
+        "url" => String::from(concat!(
+            r"(?P<scheme>[A-Za-z][A-Za-z0-9+.-]*)://",
+            r"(?:(?P<userinfo>[^\s/?#@]*)@)?",
+            r"(?P<host>\[[^\s/?#\]]*\]|[^\s/?#:]+)",
+            r"(?::(?P<port>[0-9]+))?",
+            r"(?P<path>/[^\s?#]*)?",
+            r"(?:\?(?P<query>[^\s#]*))?",
+            r"(?:#(?P<fragment>\S*))?",
+        )),
+        "semver" if strict => String::from(concat!(
+            r"(?P<major>0|[1-9][0-9]*)\.(?P<minor>0|[1-9][0-9]*)\.(?P<patch>0|[1-9][0-9]*)",
+            r"(?:-(?P<prerelease>(?:0|[1-9][0-9]*|[0-9]*[A-Za-z-][0-9A-Za-z-]*)(?:\.(?:0|[1-9][0-9]*|[0-9]*[A-Za-z-][0-9A-Za-z-]*))*))?",
+            r"(?:\+(?P<buildmetadata>[0-9A-Za-z-]+(?:\.[0-9A-Za-z-]+)*))?",
+        )),
+        "semver" => String::from(concat!(
+            r"[vV]?(?P<major>[0-9]+)\.(?P<minor>[0-9]+)(?:\.(?P<patch>[0-9]+))?",
+            r"(?:-(?P<prerelease>[0-9A-Za-z.-]+))?",
+            r"(?:\+(?P<buildmetadata>[0-9A-Za-z.-]+))?",
+        )),
+        "iso8601" if strict => String::from(concat!(
+            r"(?P<year>[0-9]{4})-(?P<month>0[1-9]|1[0-2])-(?P<day>0[1-9]|[12][0-9]|3[01])",
+            r"(?:[Tt ](?P<hour>[01][0-9]|2[0-3]):(?P<minute>[0-5][0-9])",
+            r"(?::(?P<second>[0-5][0-9]|60)(?:[.,](?P<fraction>[0-9]+))?)?",
+            r"(?P<offset>[Zz]|[+-](?:[01][0-9]|2[0-3]):[0-5][0-9])?)?",
+        )),
+        "iso8601" => String::from(concat!(
+            r"(?P<year>[0-9]{4})-?(?P<month>[0-9]{2})-?(?P<day>[0-9]{2})",
+            r"(?:[Tt ](?P<hour>[0-9]{2}):?(?P<minute>[0-9]{2})",
+            r"(?::?(?P<second>[0-9]{2})(?:[.,](?P<fraction>[0-9]+))?)?",
+            r"(?P<offset>[Zz]|[+-][0-9]{2}(?::?[0-9]{2})?)?)?",
+        )),
+        "creditCard" if strict => String::from(concat!(
+            r"(?P<number>4[0-9]{12}(?:[0-9]{3}){0,2}",
+            r"|(?:5[1-5][0-9]{2}|222[1-9]|22[3-9][0-9]|2[3-6][0-9]{2}|27[01][0-9]|2720)[0-9]{12}",
+            r"|3[47][0-9]{13}",
+            r"|6(?:011|5[0-9]{2})[0-9]{12}",
+            r"|3(?:0[0-5]|[68][0-9])[0-9]{11}",
+            r"|(?:2131|1800|35[0-9]{3})[0-9]{11})",
+        )),
+        "creditCard" => String::from(r"(?P<number>[0-9](?:[ -]?[0-9]){12,18})"),
+        _ => return None,
+    };
+    Some(pattern)
+}
+
+/// The address part of the strict `ipv6` preset, from the grok library.
+fn ipv6() -> String {
+    let definitions: BTreeMap<String, String> = grok::BASE
+        .iter()
+        .map(|(name, pattern)| (String::from(*name), String::from(*pattern)))
+        .collect();
+    match grok::expand("%{IPV6}", &definitions) {
+        Ok(pattern) => pattern,
+        Err(_) => unreachable!("the base library is valid"),
+    }
+}
+
+/// The strict `ipv4` preset without named groups, to embed it in the
+/// strict `url` preset.
+fn ipv4() -> String {
+    format!(r"(?:{0})\.(?:{0})\.(?:{0})\.(?:{0})", OCTET)
+}
+
+fn preset_pattern(name: &str, options: &PresetOptions) -> Result<String> {
+    let pattern = source(name, options.strictness).ok_or_else(|| {
+        serde_wasm_bindgen::Error::new(format!(
+            "unknown preset '{}', expected one of {}",
+            name,
+            NAMES.join(", ")
+        ))
+    })?;
+    if options.anchored {
+        Ok(format!("^(?:{})$", pattern))
+    } else {
+        Ok(pattern)
+    }
+}
+
+thread_local! {
+    /// The compiled presets, by pattern, apart from the cache of
+    /// `RRegex.cached` so they don't take its space nor get evicted.
+    static COMPILED: RefCell<HashMap<String, regex::Regex>> = RefCell::new(HashMap::new());
+}
+
+/// Returns the compiled `pattern` of a preset, compiling it on first use.
+fn compiled(pattern: String) -> Result<regex::Regex> {
+    COMPILED.with(|compiled| {
+        if let Some(regex) = compiled.borrow().get(&pattern) {
+            return Ok(regex.clone());
+        }
+        let regex = RegexOptions::default()
+            .regex_builder(&pattern)
+            .build()
+            .map_err(serde_wasm_bindgen::Error::new)?;
+        compiled.borrow_mut().insert(pattern, regex.clone());
+        Ok(regex)
+    })
+}
+
+/// Vetted regular expressions for common formats, like email addresses,
+/// IP addresses, UUIDs, URLs, versions, dates or credit card numbers,
+/// every one with named groups for its components and two strictness
+/// levels. See `PresetName` for the formats and what each level accepts.
+///
+/// Presets are compiled once, on first use, and kept apart from the cache
+/// of `RRegex.cached`, see `RRegexCache`.
+///
+/// # Example
+///
+/// ```typescript
+/// import { RPresets } from "rregex"
+///
+/// const semver = RPresets.get("semver", { anchored: true })
+/// const { name } = semver.captures("1.12.0-beta.1+build.5")
+/// expect(name.minor.value).toBe("12")
+/// expect(name.prerelease.value).toBe("beta.1")
+/// expect(semver.isMatch("v1.2")).toBe(false)
+/// expect(RPresets.get("semver", { strictness: "loose" }).isMatch("v1.2")).toBe(true)
+/// ```
+#[wasm_bindgen]
+pub struct RPresets {}
+
+#[wasm_bindgen]
+impl RPresets {
+    /// Returns the compiled regular expression of a preset.
+    ///
+    /// @param {PresetName} name - The name of the preset
+    /// @param {PresetOptions} options - The strictness and whether to anchor the pattern
+    /// @returns {RRegex}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn get(name: &str, options: JsValue) -> Result<RRegex> {
+        let options: PresetOptions = types::options(options)?;
+        let regex = compiled(preset_pattern(name, &options)?)?;
+        RRegex::from_regex(regex, RegexOptions::default())
+    }
+
+    /// Returns the pattern of a preset, e.g. to use it in another engine. To
+    /// embed a preset in another pattern, give the `RRegex` returned by
+    /// `get` to `RRegex.fromTemplate`, which escapes strings.
+    ///
+    /// @param {PresetName} name - The name of the preset
+    /// @param {PresetOptions} options - The strictness and whether to anchor the pattern
+    /// @returns {string}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn pattern(name: &str, options: JsValue) -> Result<String> {
+        let options: PresetOptions = types::options(options)?;
+        preset_pattern(name, &options)
+    }
+
+    /// Returns the names of every preset.
+    ///
+    /// @returns {PresetName[]}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn names() -> Vec<String> {
+        NAMES.iter().map(|name| String::from(*name)).collect()
+    }
+}
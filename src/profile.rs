@@ -0,0 +1,136 @@
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+#[wasm_bindgen(typescript_custom_section)]
+const REGEX_STATS_TYPE: &'static str = r#"/**
+ * The counters recorded by `RRegex` and `RRegexSet` when compiled with
+ * `profile: true`, by method name (e.g. `findAll`). Only the methods that
+ * were called are listed.
+ */
+export type RegexStats = Record<string, MethodStats>
+
+/**
+ * The counters of a single search method, accumulated since the regular
+ * expression was compiled or since the last `resetStats`.
+ */
+export type MethodStats = {
+  /** The number of calls. */
+  calls: number
+
+  /**
+   * The number of bytes of the haystacks given, from the starting offset
+   * for methods like `findAt`. Searches may stop before reading them all.
+   */
+  bytesScanned: number
+
+  /**
+   * The number of matches found: `1` for a single match (or `isMatch`
+   * returning `true`), the number of matches replaced by `replace` and the
+   * number of splits, and the number of patterns that matched in a set.
+   */
+  matches: number
+
+  /**
+   * The number of times the cache of the lazy DFA was cleared because it
+   * was full, which is what makes lazy DFAs slow on some patterns. Only
+   * counted with `engine: "hybrid"`, the other engines always report `0`.
+   */
+  cacheClears: number
+
+  /**
+   * The total time spent in the method, in milliseconds, measured with
+   * `performance.now()`. Note that some runtimes, like Cloudflare Workers,
+   * only advance the clock on I/O, in which case this stays `0`.
+   */
+  timeMs: number
+}"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = performance, js_name = now)]
+    fn performance_now() -> f64;
+}
+
+#[derive(Serialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+struct MethodStats {
+    calls: usize,
+    bytes_scanned: usize,
+    matches: usize,
+    cache_clears: usize,
+    time_ms: f64,
+}
+
+/// The counters of the search methods of a regular expression compiled
+/// with `profile: true`.
+#[derive(Default)]
+pub struct Profile {
+    methods: RefCell<BTreeMap<&'static str, MethodStats>>,
+}
+
+impl Profile {
+    /// Runs `search`, which returns its result and the number of matches it
+    /// found, and adds it to the counters of `method`. `cache_clears`
+    /// returns the number of times the lazy DFA cache was cleared so far.
+    pub fn measure<T, C, F>(
+        &self,
+        method: &'static str,
+        bytes: usize,
+        cache_clears: C,
+        search: F,
+    ) -> Result<T>
+    where
+        C: Fn() -> usize,
+        F: FnOnce() -> Result<(T, usize)>,
+    {
+        let clears = cache_clears();
+        let start = performance_now();
+        let result = search();
+        let time_ms = performance_now() - start;
+
+        let mut methods = self.methods.borrow_mut();
+        let stats = methods.entry(method).or_default();
+        stats.calls += 1;
+        stats.bytes_scanned += bytes;
+        stats.matches += result.as_ref().map_or(0, |(_, matches)| *matches);
+        stats.cache_clears += cache_clears() - clears;
+        stats.time_ms += time_ms;
+        result.map(|(value, _)| value)
+    }
+
+    pub fn reset(&self) {
+        self.methods.borrow_mut().clear();
+    }
+}
+
+/// Returns the counters of `profile` by method name, an empty object when
+/// profiling is disabled.
+pub fn stats(profile: Option<&Profile>) -> Result<JsValue> {
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+    match profile {
+        Some(profile) => profile.methods.borrow().serialize(&serializer),
+        None => BTreeMap::<&str, MethodStats>::new().serialize(&serializer),
+    }
+}
+
+/// Runs `search` through `profile` when profiling is enabled.
+pub fn measure<T, C, F>(
+    profile: Option<&Profile>,
+    method: &'static str,
+    bytes: usize,
+    cache_clears: C,
+    search: F,
+) -> Result<T>
+where
+    C: Fn() -> usize,
+    F: FnOnce() -> Result<(T, usize)>,
+{
+    match profile {
+        Some(profile) => profile.measure(method, bytes, cache_clears, search),
+        None => search().map(|(value, _)| value),
+    }
+}
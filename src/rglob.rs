@@ -0,0 +1,86 @@
+use crate::glob;
+use crate::glob::GlobOptions;
+use crate::options::RegexOptions;
+use crate::rregex::RRegex;
+use crate::types;
+use wasm_bindgen::prelude::*;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+/// A shell glob compiled into a regular expression that matches whole paths.
+///
+/// The following syntax is supported:
+///
+/// - `?` matches any single character.
+/// - `*` matches zero or more characters.
+/// - `**` recursively matches directories, but only when it's a whole path
+///   component: `**/foo`, `foo/**` and `foo/**/bar`. Anywhere else it
+///   behaves like `*`.
+/// - `[ab]` matches `a` or `b`, `[!ab]` or `[^ab]` matches any character
+///   except `a` and `b`, and ranges like `[a-z]` are allowed.
+/// - `{a,b}` matches `a` or `b`, where `a` and `b` are globs themselves.
+/// - `\` escapes the special meaning of the next character.
+///
+/// # Example
+///
+/// ```typescript
+/// import { RGlob } from "rregex"
+///
+/// const glob = new RGlob("src/**/*.{js,ts}", { literalSeparator: true })
+/// expect(glob.isMatch("src/lib/index.ts")).toBe(true)
+/// expect(glob.isMatch("test/index.ts")).toBe(false)
+/// expect(glob.toString()).toBe("(?s)^src(?:/|/.*/)[^/]*\\.(?:js|ts)$")
+/// ```
+///
+/// @see https://docs.rs/globset/latest/globset/
+#[wasm_bindgen]
+pub struct RGlob {
+    glob: String,
+    regex: RRegex,
+}
+
+#[wasm_bindgen]
+impl RGlob {
+    /// Compiles a glob. If the glob is invalid (e.g. it has an unclosed
+    /// class or alternate group), then a `PatternError` is thrown.
+    ///
+    /// @param {string} glob - The glob
+    /// @param {GlobOptions} options - The options used to compile the glob
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(glob: &str, options: JsValue) -> Result<RGlob> {
+        let options: GlobOptions = types::options(options)?;
+        let pattern = glob::to_regex(glob, &options)?;
+        let regex = RRegex::with_options(&pattern, RegexOptions::default())?;
+
+        Ok(RGlob {
+            glob: glob.to_owned(),
+            regex,
+        })
+    }
+
+    /// Returns true if and only if the glob matches the whole path given.
+    ///
+    /// @param {string} path - The path to match
+    /// @returns {boolean}
+    #[wasm_bindgen(skip_jsdoc, js_name = isMatch)]
+    pub fn is_match(&self, path: &str) -> Result<bool> {
+        self.regex.is_match(path)
+    }
+
+    /// Returns the glob this was compiled from.
+    pub fn glob(&self) -> String {
+        self.glob.clone()
+    }
+
+    /// Returns the generated regular expression into a high level
+    /// intermediate representation.
+    pub fn syntax(&self) -> Result<JsValue> {
+        self.regex.syntax()
+    }
+
+    /// Returns the generated regular expression.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn as_string(&self) -> String {
+        self.regex.as_string()
+    }
+}
@@ -0,0 +1,106 @@
+use crate::glob;
+use crate::glob::GlobOptions;
+use crate::options::RegexOptions;
+use crate::rregexset::RRegexSet;
+use crate::types;
+use crate::types::Hir;
+use std::convert::TryInto;
+use wasm_bindgen::prelude::*;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+/// Match a path against many globs in a single scan, reporting which of
+/// them match. See `RGlob` for the supported syntax.
+///
+/// # Example
+///
+/// ```typescript
+/// import { RGlobSet } from "rregex"
+///
+/// const set = new RGlobSet(["*.rs", "src/**", "**/*.{md,txt}"])
+/// expect(set.matches("src/README.md")).toEqual([1, 2])
+/// ```
+///
+/// @see https://docs.rs/globset/latest/globset/struct.GlobSet.html
+#[wasm_bindgen]
+pub struct RGlobSet {
+    globs: Vec<String>,
+    set: RRegexSet,
+}
+
+#[wasm_bindgen]
+impl RGlobSet {
+    /// Compiles a set of globs, the same options are used for all of them.
+    /// If any glob is invalid, then a `PatternError` is thrown.
+    ///
+    /// @param {string[]} list - The globs in the set
+    /// @param {GlobOptions} options - The options used to compile the globs
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(list: &js_sys::Array, options: JsValue) -> Result<RGlobSet> {
+        let options: GlobOptions = types::options(options)?;
+        let mut globs = Vec::with_capacity(list.length() as usize);
+        let mut patterns = Vec::with_capacity(list.length() as usize);
+        for (position, item) in list.iter().enumerate() {
+            let glob = item.as_string().ok_or_else(|| {
+                serde_wasm_bindgen::Error::new(format!(
+                    "item in position {} is not a string",
+                    position
+                ))
+            })?;
+            patterns.push(glob::to_regex(&glob, &options)?);
+            globs.push(glob);
+        }
+
+        let set = RRegexSet::with_options(patterns, RegexOptions::default())?;
+        Ok(RGlobSet { globs, set })
+    }
+
+    /// Returns true if and only if one of the globs in this set matches the
+    /// whole path given.
+    ///
+    /// @param {string} path - The path to match
+    /// @returns {boolean}
+    #[wasm_bindgen(skip_jsdoc, js_name = isMatch)]
+    pub fn is_match(&self, path: &str) -> Result<bool> {
+        self.set.is_match(path)
+    }
+
+    /// Returns the index of each glob that matches the whole path given, in
+    /// the order they were given to the constructor.
+    ///
+    /// @param {string} path - The path to match
+    /// @returns {number[]}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn matches(&self, path: &str) -> Result<Vec<JsValue>> {
+        self.set.matches(path)
+    }
+
+    /// Returns the globs this set was compiled from.
+    pub fn globs(&self) -> Vec<String> {
+        self.globs.clone()
+    }
+
+    /// Returns the generated regular expression of each glob.
+    pub fn regexes(&self) -> Vec<String> {
+        self.set.patterns().to_vec()
+    }
+
+    /// Returns the generated regular expression of each glob into a high
+    /// level intermediate representation.
+    ///
+    /// @returns {Hir[]}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn syntax(&self) -> Result<Vec<JsValue>> {
+        let mut parser = RegexOptions::default().parser();
+        self.set
+            .patterns()
+            .iter()
+            .map(|pattern| {
+                let hir = parser
+                    .parse(pattern)
+                    .map_err(serde_wasm_bindgen::Error::new)?;
+                Hir::from(&hir).try_into()
+            })
+            .collect()
+    }
+}
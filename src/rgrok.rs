@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+use crate::grok;
+use crate::options::RegexOptions;
+use crate::rregex::RRegex;
+use crate::types;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+/// A library of named patterns, which reference one another with the
+/// `%{NAME}` syntax of Logstash's grok filter, to build regular
+/// expressions out of readable pieces, e.g. to parse logs.
+///
+/// - `%{NAME}` matches the pattern `NAME`, in a non-capturing group.
+/// - `%{NAME:field}` matches the pattern `NAME` in a group named `field`.
+///   A type like `%{NUMBER:bytes:int}` is accepted and ignored, captures
+///   are always strings.
+///
+/// References are expanded recursively, and a pattern that references
+/// itself throws. Every `RGrok` starts with a base library with patterns
+/// like `WORD`, `INT`, `NUMBER`, `IPV4`, `IPV6`, `IP`, `HOSTNAME`, `URI`,
+/// `URIPATH`, `PATH`, `UUID`, `LOGLEVEL`, `TIMESTAMP_ISO8601`, `HTTPDATE`
+/// or `COMMONAPACHELOG`, see `get`.
+///
+/// # Example
+///
+/// ```typescript
+/// import { RGrok } from "rregex"
+///
+/// const grok = new RGrok({ METHOD: "GET|POST|PUT|DELETE" })
+/// const re = grok.compile("^%{IP:client} %{METHOD:method} %{URIPATH:path}$")
+/// const { name } = re.captures("10.0.0.1 GET /index.html")
+/// expect(name.client.value).toBe("10.0.0.1")
+/// expect(name.method.value).toBe("GET")
+/// expect(name.path.value).toBe("/index.html")
+/// ```
+///
+/// @see https://www.elastic.co/guide/en/logstash/current/plugins-filters-grok.html
+#[wasm_bindgen]
+pub struct RGrok {
+    definitions: BTreeMap<String, String>,
+}
+
+#[wasm_bindgen]
+impl RGrok {
+    /// Creates a library with the base patterns and `definitions`, which
+    /// replace the base patterns with the same name.
+    ///
+    /// @param {Record<string, string>} definitions - Patterns by name
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(definitions: JsValue) -> Result<RGrok> {
+        let mut grok = RGrok {
+            definitions: grok::BASE
+                .iter()
+                .map(|(name, pattern)| (String::from(*name), String::from(*pattern)))
+                .collect(),
+        };
+        let definitions: BTreeMap<String, String> = types::options(definitions)?;
+        for (name, pattern) in definitions {
+            grok.add(&name, &pattern)?;
+        }
+        Ok(grok)
+    }
+
+    /// Defines the pattern `name`, replacing any previous definition. Names
+    /// are made of ASCII letters, digits and `_`. References in `pattern`
+    /// are only resolved when it's used, so patterns can be added in any
+    /// order.
+    ///
+    /// @param {string} name - The name used in references
+    /// @param {string} pattern - The pattern, which may reference other patterns
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn add(&mut self, name: &str, pattern: &str) -> Result<()> {
+        if !grok::is_name(name) {
+            return Err(serde_wasm_bindgen::Error::new(format!(
+                "invalid pattern name '{}', expected ASCII letters, digits and '_'",
+                name
+            )));
+        }
+        self.definitions
+            .insert(String::from(name), String::from(pattern));
+        Ok(())
+    }
+
+    /// Returns the definition of the pattern `name`, without expanding it,
+    /// or `undefined`.
+    ///
+    /// @param {string} name - The name of the pattern
+    /// @returns {string | undefined}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.definitions.get(name).cloned()
+    }
+
+    /// Returns `pattern` with its references expanded, which is the regular
+    /// expression compiled by `compile`. Throws a `PatternError` when a
+    /// reference is invalid, unknown, or references itself, or when the
+    /// expanded pattern is longer than 1 MiB.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RGrok } from "rregex"
+    ///
+    /// const grok = new RGrok({ PAIR: "%{WORD:key}=%{INT:value}" })
+    /// expect(grok.expand("%{PAIR}")).toBe("(?:(?P<key>\\b\\w+\\b)=(?P<value>[+-]?[0-9]+))")
+    /// ```
+    ///
+    /// @param {string} pattern - A pattern with references
+    /// @returns {string}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn expand(&self, pattern: &str) -> Result<String> {
+        Ok(grok::expand(pattern, &self.definitions)?)
+    }
+
+    /// Compiles `pattern` with its references expanded.
+    ///
+    /// @param {string} pattern - A pattern with references
+    /// @param {RegexOptions} options - The options used to compile the regular expression
+    /// @returns {RRegex}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn compile(&self, pattern: &str, options: JsValue) -> Result<RRegex> {
+        let options: RegexOptions = types::options(options)?;
+        RRegex::with_options(&self.expand(pattern)?, options)
+    }
+}
@@ -0,0 +1,324 @@
+use crate::glob;
+use crate::glob::GlobOptions;
+use crate::options::RegexOptions;
+use crate::rregexset::RRegexSet;
+use crate::types;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+#[wasm_bindgen(typescript_custom_section)]
+const IGNORE_TYPES: &'static str = r#"/**
+ * Options accepted by `RIgnoreMatcher`.
+ */
+export type IgnoreOptions = {
+  /**
+   * The directory the rules are relative to, usually the directory of the
+   * `.gitignore` file. It's stripped from the paths given to `matched`, so
+   * both `src/main.rs` and `/repo/src/main.rs` can be matched when the base
+   * is `/repo`.
+   */
+  base?: string
+
+  /** Whether to match paths case insensitively. Defaults to `false`. */
+  caseInsensitive?: boolean
+}
+
+/**
+ * The rule that decided whether a path is ignored.
+ */
+export type IgnoreMatch = {
+  /** The index of the rule, counting only rules (not comments or blank lines). */
+  index: number
+
+  /** The line of the rule, starting at `1`. */
+  line: number
+
+  /** The rule, as it was written. */
+  rule: string
+
+  /**
+   * Whether the rule starts with `!`, in which case the path is explicitly
+   * not ignored.
+   */
+  negated: boolean
+}"#;
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct IgnoreOptions {
+    base: Option<String>,
+    case_insensitive: bool,
+}
+
+struct IgnoreRule {
+    line: usize,
+    original: String,
+    negated: bool,
+    dir_only: bool,
+}
+
+#[derive(Serialize)]
+struct IgnoreMatch<'r> {
+    index: usize,
+    line: usize,
+    rule: &'r str,
+    negated: bool,
+}
+
+/// Matches paths against an ordered list of rules with the semantics of a
+/// `.gitignore` file:
+///
+/// - Blank lines and lines starting with `#` are skipped, trailing spaces
+///   are ignored unless escaped with `\`.
+/// - A rule starting with `!` negates a previous rule, so the paths it
+///   matches are not ignored.
+/// - A rule ending with `/` only matches directories.
+/// - A rule with a `/` at the start or in the middle is anchored to the
+///   base directory, otherwise it matches at any depth.
+/// - `*`, `?` and `[...]` never match `/`, and `**` matches any number of
+///   directories.
+/// - When several rules match a path, the last one wins.
+///
+/// All the rules are compiled into a single `RRegexSet`, so a path is
+/// matched against all of them in a single scan.
+///
+/// # Example
+///
+/// ```typescript
+/// import { RIgnoreMatcher } from "rregex"
+///
+/// const matcher = new RIgnoreMatcher("# build output\ntarget/\n*.log\n!keep.log")
+/// expect(matcher.matched("target", true)).toEqual({ index: 0, line: 2, rule: "target/", negated: false })
+/// expect(matcher.matched("target", false)).toBe(undefined)
+/// expect(matcher.isIgnored("logs/app.log", false)).toBe(true)
+/// expect(matcher.isIgnored("logs/keep.log", false)).toBe(false)
+/// ```
+///
+/// @see https://git-scm.com/docs/gitignore
+#[wasm_bindgen]
+pub struct RIgnoreMatcher {
+    rules: Vec<IgnoreRule>,
+    set: RRegexSet,
+    base: Option<String>,
+}
+
+#[wasm_bindgen]
+impl RIgnoreMatcher {
+    /// Compiles a list of rules, either the contents of a `.gitignore` file
+    /// or an array with one line per item.
+    ///
+    /// If a rule is an invalid glob (e.g. it has an unclosed class), then a
+    /// `PatternError` is thrown.
+    ///
+    /// @param {string | string[]} rules - The rules, in order
+    /// @param {IgnoreOptions} options - The base directory and case sensitivity of the rules
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(rules: JsValue, options: JsValue) -> Result<RIgnoreMatcher> {
+        let options: IgnoreOptions = types::options(options)?;
+        let lines: Vec<String> = match rules.dyn_ref::<js_sys::Array>() {
+            Some(list) => list
+                .iter()
+                .enumerate()
+                .map(|(position, item)| {
+                    item.as_string().ok_or_else(|| {
+                        serde_wasm_bindgen::Error::new(format!(
+                            "item in position {} is not a string",
+                            position
+                        ))
+                    })
+                })
+                .collect::<Result<_>>()?,
+            None => rules
+                .as_string()
+                .ok_or_else(|| {
+                    serde_wasm_bindgen::Error::new("rules are not a string or an array")
+                })?
+                .lines()
+                .map(String::from)
+                .collect(),
+        };
+
+        let glob_options = GlobOptions {
+            literal_separator: true,
+            case_insensitive: options.case_insensitive,
+            backslash_escape: true,
+        };
+        let mut rules = Vec::new();
+        let mut patterns = Vec::new();
+        for (line, text) in lines.iter().enumerate() {
+            if let Some((rule, glob)) = parse_rule(line + 1, text) {
+                patterns.push(glob::to_regex(&glob, &glob_options)?);
+                rules.push(rule);
+            }
+        }
+
+        let base = options
+            .base
+            .map(|base| base.trim_end_matches('/').to_owned())
+            .filter(|base| !base.is_empty());
+        let set = RRegexSet::with_options(patterns, RegexOptions::default())?;
+        Ok(RIgnoreMatcher { rules, set, base })
+    }
+
+    /// Returns the last rule that matches the path given, or `undefined` if
+    /// no rule matches it. The path is ignored when the rule isn't negated.
+    ///
+    /// Only the path itself is matched, not its parents: with a `target/`
+    /// rule, `target/debug` is not matched, since git doesn't even look
+    /// inside of an ignored directory. Use `matchedPathOrAnyParents` to
+    /// match paths that aren't visited by walking directories.
+    ///
+    /// @param {string} path - The path, relative to the base directory or starting with it
+    /// @param {boolean} isDir - Whether the path is a directory
+    /// @returns {IgnoreMatch | undefined}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn matched(&self, path: &str, is_dir: bool) -> Result<JsValue> {
+        let path = self.strip(path);
+        let index = self.winner(path, is_dir);
+        self.to_value(index)
+    }
+
+    /// Returns the same as `matched` for the path given, or for the closest
+    /// of its parent directories that is matched by a rule.
+    ///
+    /// @param {string} path - The path, relative to the base directory or starting with it
+    /// @param {boolean} isDir - Whether the path is a directory
+    /// @returns {IgnoreMatch | undefined}
+    #[wasm_bindgen(skip_jsdoc, js_name = matchedPathOrAnyParents)]
+    pub fn matched_path_or_any_parents(&self, path: &str, is_dir: bool) -> Result<JsValue> {
+        let mut path = self.strip(path);
+        let mut is_dir = is_dir;
+        loop {
+            if let Some(index) = self.winner(path, is_dir) {
+                return self.to_value(Some(index));
+            }
+            match path.rfind('/') {
+                Some(separator) => path = &path[..separator],
+                None => return Ok(JsValue::UNDEFINED),
+            }
+            is_dir = true;
+        }
+    }
+
+    /// Returns true if and only if the last rule that matches the path given
+    /// isn't negated.
+    ///
+    /// @param {string} path - The path, relative to the base directory or starting with it
+    /// @param {boolean} isDir - Whether the path is a directory
+    /// @returns {boolean}
+    #[wasm_bindgen(skip_jsdoc, js_name = isIgnored)]
+    pub fn is_ignored(&self, path: &str, is_dir: bool) -> bool {
+        let path = self.strip(path);
+        self.winner(path, is_dir)
+            .is_some_and(|index| !self.rules[index].negated)
+    }
+
+    /// Returns the number of rules, without comments and blank lines.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.rules.len()
+    }
+}
+
+impl RIgnoreMatcher {
+    /// Returns `path` relative to the base directory.
+    fn strip<'p>(&self, path: &'p str) -> &'p str {
+        let mut path = path;
+        if let Some(base) = &self.base {
+            if let Some(relative) = path.strip_prefix(base.as_str()) {
+                if relative.is_empty() || relative.starts_with('/') {
+                    path = relative;
+                }
+            }
+        }
+        while let Some(relative) = path.strip_prefix("./") {
+            path = relative;
+        }
+        path.trim_start_matches('/').trim_end_matches('/')
+    }
+
+    /// Returns the index of the last rule that matches `path`.
+    fn winner(&self, path: &str, is_dir: bool) -> Option<usize> {
+        self.set
+            .set_matches(path)
+            .iter()
+            .filter(|index| is_dir || !self.rules[*index].dir_only)
+            .max()
+    }
+
+    fn to_value(&self, index: Option<usize>) -> Result<JsValue> {
+        let found = index.map(|index| {
+            let rule = &self.rules[index];
+            IgnoreMatch {
+                index,
+                line: rule.line,
+                rule: &rule.original,
+                negated: rule.negated,
+            }
+        });
+        serde_wasm_bindgen::to_value(&found)
+    }
+}
+
+/// Parses a line of a `.gitignore` file into a rule and the glob it
+/// matches, returns `None` for blank lines and comments.
+fn parse_rule(line: usize, text: &str) -> Option<(IgnoreRule, String)> {
+    let text = text.strip_suffix('\r').unwrap_or(text);
+    if text.starts_with('#') {
+        return None;
+    }
+
+    // trailing spaces are ignored unless they are escaped
+    let mut pattern = text;
+    while pattern.ends_with(' ') && !pattern.ends_with("\\ ") {
+        pattern = &pattern[..pattern.len() - 1];
+    }
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let negated = pattern.starts_with('!');
+    if negated {
+        pattern = &pattern[1..];
+    }
+    let dir_only = pattern.ends_with('/') && !pattern.ends_with("\\/");
+    if dir_only {
+        pattern = &pattern[..pattern.len() - 1];
+    }
+    let anchored = pattern.contains('/');
+    pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+    if pattern.is_empty() {
+        return None;
+    }
+
+    // braces are not special in a `.gitignore` file
+    let mut glob = String::with_capacity(pattern.len() + 3);
+    if !anchored {
+        glob.push_str("**/");
+    }
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                glob.push(c);
+                glob.extend(chars.next());
+            }
+            '{' | '}' | ',' => {
+                glob.push('\\');
+                glob.push(c);
+            }
+            c => glob.push(c),
+        }
+    }
+
+    let rule = IgnoreRule {
+        line,
+        original: text.to_owned(),
+        negated,
+        dir_only,
+    };
+    Some((rule, glob))
+}
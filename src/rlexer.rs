@@ -0,0 +1,397 @@
+use regex_automata::{meta, Anchored, Input, MatchKind, PatternSet};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+use crate::options::RegexOptions;
+use crate::types;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+/// The kind of the tokens of unmatched input.
+const ERROR: &str = "error";
+
+#[wasm_bindgen(typescript_custom_section)]
+const LEXER_TYPES: &'static str = r#"/**
+ * A rule of a `RLexer`, producing tokens of kind `name`.
+ */
+export type LexerRule = {
+  /** The kind of the tokens, which can't be `error`. */
+  name: string
+
+  /** The regular expression matching the tokens. */
+  pattern: string
+
+  /** Drops the tokens, e.g. for whitespace or comments. Defaults to `false`. */
+  skip?: boolean
+
+  /** Enters the mode `push` after a token, until a rule pops it. */
+  push?: string
+
+  /** Returns to the previous mode after a token. Defaults to `false`. */
+  pop?: boolean
+}
+
+/**
+ * Options used to build a `RLexer`. Besides the flags, only `sizeLimit` of
+ * `RegexOptions` is used.
+ */
+export type LexerOptions = RegexOptions & {
+  /** The mode the lexer starts in. Defaults to `main`. */
+  initialMode?: string
+
+  /**
+   * How a token is chosen when several rules match at the same position:
+   * `longest` picks the longest match, and the first rule among the ones
+   * as long, `first` picks the first rule. Either way, a rule matches as
+   * much as it can, so `=|==` matches `==`. Defaults to `longest`.
+   */
+  matchKind?: 'longest' | 'first'
+}
+
+/**
+ * A token produced by a `RLexer`. The line and column start at `1`, and
+ * columns are counted in code points.
+ */
+export type Token = Match & {
+  /** The name of the rule that matched, or `error` for unmatched input. */
+  kind: string
+  line: number
+  column: number
+}"#;
+
+#[derive(Deserialize)]
+struct LexerRule {
+    name: String,
+    pattern: String,
+    #[serde(default)]
+    skip: bool,
+    #[serde(default)]
+    push: Option<String>,
+    #[serde(default)]
+    pop: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LexerRules {
+    Rules(Vec<LexerRule>),
+    Modes(BTreeMap<String, Vec<LexerRule>>),
+}
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum LexerMatchKind {
+    #[default]
+    Longest,
+    First,
+}
+
+#[derive(Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct LexerOptions {
+    initial_mode: String,
+    match_kind: LexerMatchKind,
+}
+
+impl Default for LexerOptions {
+    fn default() -> Self {
+        LexerOptions {
+            initial_mode: String::from("main"),
+            match_kind: LexerMatchKind::default(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Token<'t> {
+    kind: &'t str,
+    value: &'t str,
+    start: usize,
+    end: usize,
+    line: usize,
+    column: usize,
+}
+
+/// A rule of a mode, where `push` is the index of a mode.
+struct Rule {
+    name: String,
+    skip: bool,
+    push: Option<usize>,
+    pop: bool,
+}
+
+/// The rules of a mode, compiled with `MatchKind::All` to report every rule
+/// matching at a position, and the longest match of each.
+struct Mode {
+    name: String,
+    rules: Vec<Rule>,
+    regex: meta::Regex,
+}
+
+/// Splits a string into tokens, with a list of rules tried at every
+/// position, all at once, by a multi-pattern automaton anchored at the
+/// position. When no rule matches, the input is reported in a token of
+/// kind `error` until a rule matches again. Rules never produce empty
+/// tokens, empty matches are ignored.
+///
+/// Rules can be grouped in modes, for languages whose tokens depend on the
+/// context, like the strings with interpolations of templates: a rule with
+/// `push` enters another mode, and a rule with `pop` returns to the
+/// previous one.
+///
+/// # Example
+///
+/// ```typescript
+/// import { RLexer } from "rregex"
+///
+/// const lexer = new RLexer({
+///   main: [
+///     { name: "ws", pattern: "\\s+", skip: true },
+///     { name: "number", pattern: "[0-9]+" },
+///     { name: "if", pattern: "if" },
+///     { name: "ident", pattern: "[a-z]+" },
+///     { name: "quote", pattern: "\"", push: "string" },
+///   ],
+///   string: [
+///     { name: "text", pattern: "[^\"]+" },
+///     { name: "quote", pattern: "\"", pop: true },
+///   ],
+/// })
+/// expect(lexer.tokenize("if iffy \"hi\" 42").map((t) => [t.kind, t.value])).toEqual([
+///   ["if", "if"],
+///   ["ident", "iffy"],
+///   ["quote", "\""],
+///   ["text", "hi"],
+///   ["quote", "\""],
+///   ["number", "42"],
+/// ])
+/// ```
+///
+/// `if` is a keyword above, since both `if` and `ident` match it and `if`
+/// comes first, while `iffy` is an `ident`, since it's the longest match.
+#[wasm_bindgen]
+pub struct RLexer {
+    modes: Vec<Mode>,
+    initial: usize,
+    match_kind: LexerMatchKind,
+}
+
+#[wasm_bindgen]
+impl RLexer {
+    /// Builds a lexer from a list of rules, in the mode `main`, or from
+    /// lists of rules by mode. Throws when a pattern is invalid, a rule is
+    /// named `error`, or a mode is unknown.
+    ///
+    /// @param {LexerRule[] | Record<string, LexerRule[]>} rules - The rules, by mode
+    /// @param {LexerOptions} options - The initial mode, the match kind and the flags of the patterns
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(rules: JsValue, options: JsValue) -> Result<RLexer> {
+        let rules: LexerRules = serde_wasm_bindgen::from_value(rules)?;
+        let lexer: LexerOptions = types::options(options.clone())?;
+        let options: RegexOptions = types::options(options)?;
+
+        let modes = match rules {
+            LexerRules::Rules(rules) => vec![(String::from("main"), rules)],
+            LexerRules::Modes(modes) => modes.into_iter().collect(),
+        };
+        RLexer::with_modes(modes, lexer, options)
+    }
+
+    /// Returns the names of the modes.
+    ///
+    /// @returns {string[]}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn modes(&self) -> Vec<String> {
+        self.modes.iter().map(|mode| mode.name.clone()).collect()
+    }
+
+    /// Returns the tokens of `text`, without the ones of the rules with
+    /// `skip`. Throws when a rule pops the initial mode.
+    ///
+    /// @param {string} text - The text to split
+    /// @returns {Token[]}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn tokenize(&self, text: &str) -> Result<JsValue> {
+        serde_wasm_bindgen::to_value(&self.tokens(text)?)
+    }
+}
+
+impl RLexer {
+    /// Builds a lexer from the rules of every mode.
+    fn with_modes(
+        modes: Vec<(String, Vec<LexerRule>)>,
+        lexer: LexerOptions,
+        options: RegexOptions,
+    ) -> Result<RLexer> {
+        let index = |name: &str| modes.iter().position(|(mode, _)| mode == name);
+        let initial = index(&lexer.initial_mode).ok_or_else(|| {
+            serde_wasm_bindgen::Error::new(format!("unknown initial mode '{}'", lexer.initial_mode))
+        })?;
+
+        let mut compiled = Vec::with_capacity(modes.len());
+        for (name, rules) in &modes {
+            let mut hirs = Vec::with_capacity(rules.len());
+            let mut mode_rules = Vec::with_capacity(rules.len());
+            for rule in rules {
+                if rule.name == ERROR {
+                    return Err(serde_wasm_bindgen::Error::new(format!(
+                        "the rule name '{}' is reserved for unmatched input",
+                        ERROR
+                    )));
+                }
+                let hir = options.parser().parse(&rule.pattern).map_err(|err| {
+                    serde_wasm_bindgen::Error::new(format!(
+                        "invalid pattern in rule '{}': {}",
+                        rule.name, err
+                    ))
+                })?;
+                hirs.push(hir);
+
+                let push = match &rule.push {
+                    Some(push) => Some(index(push).ok_or_else(|| {
+                        serde_wasm_bindgen::Error::new(format!(
+                            "rule '{}' pushes the unknown mode '{}'",
+                            rule.name, push
+                        ))
+                    })?),
+                    None => None,
+                };
+                mode_rules.push(Rule {
+                    name: rule.name.clone(),
+                    skip: rule.skip,
+                    push,
+                    pop: rule.pop,
+                });
+            }
+
+            let mut config = meta::Config::new().match_kind(MatchKind::All);
+            if let Some(limit) = options.size_limit {
+                config = config.nfa_size_limit(Some(limit));
+            }
+            let regex = meta::Regex::builder()
+                .configure(config)
+                .build_many_from_hir(&hirs)
+                .map_err(serde_wasm_bindgen::Error::new)?;
+            compiled.push(Mode {
+                name: name.clone(),
+                rules: mode_rules,
+                regex,
+            });
+        }
+
+        Ok(RLexer {
+            modes: compiled,
+            initial,
+            match_kind: lexer.match_kind,
+        })
+    }
+
+    fn tokens<'t>(&'t self, text: &'t str) -> Result<Vec<Token<'t>>> {
+        let rules = self.modes.iter().map(|mode| mode.rules.len()).max();
+        let mut matching = PatternSet::new(rules.unwrap_or(0));
+        let mut stack = vec![self.initial];
+        let mut lines = Lines::default();
+        let mut tokens = Vec::new();
+        let mut unmatched = None;
+        let mut at = 0;
+        while at < text.len() {
+            let mode = &self.modes[stack[stack.len() - 1]];
+            let (index, end) = match self.token(mode, text, at, &mut matching) {
+                Some(token) => token,
+                None => {
+                    unmatched.get_or_insert(at);
+                    at += text[at..].chars().next().map_or(1, char::len_utf8);
+                    continue;
+                }
+            };
+
+            if let Some(start) = unmatched.take() {
+                tokens.push(lines.token(text, ERROR, start, at));
+            }
+            let rule = &mode.rules[index];
+            if !rule.skip {
+                tokens.push(lines.token(text, &rule.name, at, end));
+            }
+            if rule.pop {
+                if stack.len() == 1 {
+                    return Err(serde_wasm_bindgen::Error::new(format!(
+                        "rule '{}' pops the initial mode '{}' at offset {}",
+                        rule.name, mode.name, at
+                    )));
+                }
+                stack.pop();
+            }
+            if let Some(push) = rule.push {
+                stack.push(push);
+            }
+            at = end;
+        }
+        if let Some(start) = unmatched {
+            tokens.push(lines.token(text, ERROR, start, text.len()));
+        }
+        Ok(tokens)
+    }
+
+    /// Returns the index of the rule of `mode` producing the token at `at`,
+    /// and the end of the token.
+    fn token(
+        &self,
+        mode: &Mode,
+        text: &str,
+        at: usize,
+        matching: &mut PatternSet,
+    ) -> Option<(usize, usize)> {
+        let input = Input::new(text).range(at..).anchored(Anchored::Yes);
+        matching.clear();
+        mode.regex.which_overlapping_matches(&input, matching);
+
+        let mut best: Option<(usize, usize)> = None;
+        for pattern in matching.iter() {
+            let input = input.clone().anchored(Anchored::Pattern(pattern));
+            // the end of the longest match, since the search continues
+            // until no longer match is possible
+            let end = match mode.regex.search_half(&input) {
+                Some(m) if m.offset() > at => m.offset(),
+                _ => continue,
+            };
+            if best.is_none_or(|(_, longest)| end > longest) {
+                best = Some((pattern.as_usize(), end));
+            }
+            if self.match_kind == LexerMatchKind::First {
+                break;
+            }
+        }
+        best
+    }
+}
+
+/// Counts the lines and columns of successive tokens.
+#[derive(Default)]
+struct Lines {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Lines {
+    fn token<'t>(&mut self, text: &'t str, kind: &'t str, start: usize, end: usize) -> Token<'t> {
+        for c in text[self.offset..start].chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 0;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.offset = start;
+        Token {
+            kind,
+            value: &text[start..end],
+            start,
+            end,
+            line: self.line + 1,
+            column: self.column + 1,
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use aho_corasick::{AhoCorasick, MatchKind};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::types;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+#[wasm_bindgen(typescript_custom_section)]
+const LITERAL_SET_TYPES: &'static str = r#"/**
+ * Options used to build a `RLiteralSet`.
+ */
+export type LiteralSetOptions = {
+  /**
+   * How matches are reported when several literals match at the same
+   * position, see `LiteralMatchKind`. Defaults to `leftmost-first`.
+   */
+  matchKind?: LiteralMatchKind
+
+  /**
+   * Matches ASCII letters regardless of their case, other characters are
+   * matched as they are. Defaults to `false`.
+   */
+  asciiCaseInsensitive?: boolean
+}
+
+/**
+ * The match semantics of a `RLiteralSet`:
+ *
+ * - `leftmost-first` reports the leftmost match, preferring the literal
+ *   given first, like the alternation of a regular expression.
+ * - `leftmost-longest` reports the leftmost match, preferring the longest
+ *   literal, like a POSIX regular expression.
+ * - `standard` reports a match as soon as it's seen, which is the one
+ *   ending first, and is the only kind supporting `findOverlapping`.
+ *
+ * @see https://docs.rs/aho-corasick/latest/aho_corasick/enum.MatchKind.html
+ */
+export type LiteralMatchKind = 'leftmost-first' | 'leftmost-longest' | 'standard'
+
+/**
+ * A `Match` of a `RLiteralSet`, with the index of the literal that matched.
+ */
+export type LiteralMatch = Match & {
+  /** The index of the literal in the list given to the constructor. */
+  pattern: number
+}"#;
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+enum LiteralMatchKind {
+    #[default]
+    LeftmostFirst,
+    LeftmostLongest,
+    Standard,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct LiteralSetOptions {
+    match_kind: LiteralMatchKind,
+    ascii_case_insensitive: bool,
+}
+
+#[derive(Serialize)]
+struct LiteralMatch<'t> {
+    start: usize,
+    end: usize,
+    value: &'t str,
+    pattern: usize,
+}
+
+impl<'t> LiteralMatch<'t> {
+    fn new(text: &'t str, m: aho_corasick::Match) -> Self {
+        LiteralMatch {
+            start: m.start(),
+            end: m.end(),
+            value: &text[m.start()..m.end()],
+            pattern: m.pattern().as_usize(),
+        }
+    }
+}
+
+/// Searches many literal strings at once with the Aho-Corasick algorithm,
+/// which is faster to build and to search than a regular expression made
+/// of their alternation, e.g. for keyword search.
+///
+/// Offsets are byte offsets in the UTF-8 encoded text, like the ones of
+/// `RRegex`.
+///
+/// # Example
+///
+/// ```typescript
+/// import { RLiteralSet } from "rregex"
+///
+/// const set = new RLiteralSet(["apple", "maple", "Snapple"], { asciiCaseInsensitive: true })
+/// expect(set.findAll("Nobody likes maple in their apple flavored SNAPPLE.")).toEqual([
+///   { start: 13, end: 18, value: "maple", pattern: 1 },
+///   { start: 28, end: 33, value: "apple", pattern: 0 },
+///   { start: 43, end: 50, value: "SNAPPLE", pattern: 2 },
+/// ])
+/// ```
+///
+/// @see https://docs.rs/aho-corasick/latest/aho_corasick/
+#[wasm_bindgen]
+pub struct RLiteralSet {
+    automaton: AhoCorasick,
+}
+
+#[wasm_bindgen]
+impl RLiteralSet {
+    /// Builds a set searching `patterns` literally. Throws if a literal is
+    /// empty, as it would match between the bytes of a character.
+    ///
+    /// @param {string[]} patterns - The literals to search
+    /// @param {LiteralSetOptions} options - The match kind and case sensitivity
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(patterns: Vec<String>, options: JsValue) -> Result<RLiteralSet> {
+        let options: LiteralSetOptions = types::options(options)?;
+        if let Some(index) = patterns.iter().position(String::is_empty) {
+            return Err(serde_wasm_bindgen::Error::new(format!(
+                "the literal at index {} is empty, only non-empty literals can be searched",
+                index
+            )));
+        }
+        let match_kind = match options.match_kind {
+            LiteralMatchKind::LeftmostFirst => MatchKind::LeftmostFirst,
+            LiteralMatchKind::LeftmostLongest => MatchKind::LeftmostLongest,
+            LiteralMatchKind::Standard => MatchKind::Standard,
+        };
+        let automaton = AhoCorasick::builder()
+            .match_kind(match_kind)
+            .ascii_case_insensitive(options.ascii_case_insensitive)
+            .build(&patterns)
+            .map_err(serde_wasm_bindgen::Error::new)?;
+        Ok(RLiteralSet { automaton })
+    }
+
+    /// Returns true if and only if one of the literals matches in `text`.
+    ///
+    /// @param {string} text - The string to search
+    /// @returns {boolean}
+    #[wasm_bindgen(skip_jsdoc, js_name = isMatch)]
+    pub fn is_match(&self, text: &str) -> bool {
+        self.automaton.is_match(text)
+    }
+
+    /// Returns the first match in `text`, or `undefined`.
+    ///
+    /// @param {string} text - The string to search
+    /// @returns {LiteralMatch | undefined}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn find(&self, text: &str) -> Result<JsValue> {
+        match self.automaton.find(text) {
+            Some(m) => serde_wasm_bindgen::to_value(&LiteralMatch::new(text, m)),
+            None => Ok(JsValue::UNDEFINED),
+        }
+    }
+
+    /// Returns every successive non-overlapping match in `text`.
+    ///
+    /// @param {string} text - The string to search
+    /// @returns {LiteralMatch[]}
+    #[wasm_bindgen(skip_jsdoc, js_name = findAll)]
+    pub fn find_all(&self, text: &str) -> Result<JsValue> {
+        let matches: Vec<LiteralMatch> = self
+            .automaton
+            .find_iter(text)
+            .map(|m| LiteralMatch::new(text, m))
+            .collect();
+        serde_wasm_bindgen::to_value(&matches)
+    }
+
+    /// Returns every match in `text`, including the ones overlapping other
+    /// matches, e.g. `abc` and `bc` in `abcd`. Only supported with the
+    /// `standard` match kind, other kinds throw.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RLiteralSet } from "rregex"
+    ///
+    /// const set = new RLiteralSet(["abc", "bc", "cd"], { matchKind: "standard" })
+    /// expect(set.findOverlapping("abcd").map((m) => m.pattern)).toEqual([0, 1, 2])
+    /// ```
+    ///
+    /// @param {string} text - The string to search
+    /// @returns {LiteralMatch[]}
+    #[wasm_bindgen(skip_jsdoc, js_name = findOverlapping)]
+    pub fn find_overlapping(&self, text: &str) -> Result<JsValue> {
+        let matches: Vec<LiteralMatch> = self
+            .automaton
+            .try_find_overlapping_iter(text)
+            .map_err(serde_wasm_bindgen::Error::new)?
+            .map(|m| LiteralMatch::new(text, m))
+            .collect();
+        serde_wasm_bindgen::to_value(&matches)
+    }
+
+    /// Replaces every non-overlapping match in `text` with the replacement
+    /// of the literal that matched, `replacements[i]` for the literal `i`.
+    /// Replacements are inserted as they are, without expanding `$`.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RLiteralSet } from "rregex"
+    ///
+    /// const set = new RLiteralSet(["cat", "dog"])
+    /// expect(set.replaceAll("cat chases dog", ["dog", "cat"])).toBe("dog chases cat")
+    /// ```
+    ///
+    /// @param {string} text - The string to search
+    /// @param {string[]} replacements - A replacement for every literal of the set
+    /// @returns {string}
+    #[wasm_bindgen(skip_jsdoc, js_name = replaceAll)]
+    pub fn replace_all(&self, text: &str, replacements: Vec<String>) -> Result<String> {
+        if replacements.len() != self.automaton.patterns_len() {
+            return Err(serde_wasm_bindgen::Error::new(format!(
+                "expected {} replacements, one for every literal, found {}",
+                self.automaton.patterns_len(),
+                replacements.len()
+            )));
+        }
+        self.automaton
+            .try_replace_all(text, &replacements)
+            .map_err(serde_wasm_bindgen::Error::new)
+    }
+}
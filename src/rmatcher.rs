@@ -0,0 +1,179 @@
+use crate::dfa;
+use crate::options::RegexOptions;
+use crate::types;
+use regex_automata::dfa::Automaton;
+use regex_automata::{Anchored, Input};
+use wasm_bindgen::prelude::*;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+/// A matcher of whole strings, compiled into a DFA that can be combined
+/// with other matchers, to express rules like "matches A but not B" that
+/// have no equivalent in the regex syntax.
+///
+/// Unlike `RRegex`, a matcher only answers whether a string matches as a
+/// whole, as if the pattern was surrounded by `^(?:` and `)$`. Matchers are
+/// compiled into DFAs, which don't support Unicode word boundaries, use
+/// `(?-u:\b)` instead.
+///
+/// # Example
+///
+/// ```typescript
+/// import { RMatcher } from "rregex"
+///
+/// const identifier = new RMatcher("[a-z_][a-z0-9_]*")
+/// const keyword = new RMatcher("if|else|while|return")
+/// const name = RMatcher.difference(identifier, keyword)
+/// expect(name.isMatch("iffy")).toBe(true)
+/// expect(name.isMatch("if")).toBe(false)
+/// ```
+#[wasm_bindgen]
+pub struct RMatcher {
+    dfa: dfa::Dfa,
+}
+
+#[wasm_bindgen]
+impl RMatcher {
+    /// Compiles a regular expression into a matcher of whole strings.
+    ///
+    /// @param {string} re - The regular expression
+    /// @param {RegexOptions} options - The options used to parse the regular expression
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(re: &str, options: JsValue) -> Result<RMatcher> {
+        let options: RegexOptions = types::options(options)?;
+        let hir = options
+            .parser()
+            .parse(re)
+            .map_err(serde_wasm_bindgen::Error::new)?;
+        Ok(RMatcher {
+            dfa: dfa::whole_match(&hir, options.dfa_size_limit)?,
+        })
+    }
+
+    /// Returns a matcher of the strings matched by both `a` and `b`.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RMatcher } from "rregex"
+    ///
+    /// const short = new RMatcher("(?s).{1,8}")
+    /// const hex = new RMatcher("[0-9a-f]+")
+    /// const matcher = RMatcher.intersect(short, hex)
+    /// expect(matcher.isMatch("c0ffee")).toBe(true)
+    /// expect(matcher.isMatch("deadbeef00")).toBe(false)
+    /// ```
+    ///
+    /// @param {RMatcher} a - The first matcher
+    /// @param {RMatcher} b - The second matcher
+    /// @returns {RMatcher}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn intersect(a: &RMatcher, b: &RMatcher) -> Result<RMatcher> {
+        let dfa = dfa::combine(
+            &a.dfa,
+            &b.dfa,
+            |a_live, b_live| a_live && b_live,
+            |a_accepts, b_accepts| a_accepts && b_accepts,
+        )?;
+        Ok(RMatcher { dfa })
+    }
+
+    /// Returns a matcher of the strings matched by `a` but not by `b`.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RMatcher } from "rregex"
+    ///
+    /// const matcher = RMatcher.difference(new RMatcher("[a-z]+"), new RMatcher("null|true|false"))
+    /// expect(matcher.isMatch("nil")).toBe(true)
+    /// expect(matcher.isMatch("null")).toBe(false)
+    /// ```
+    ///
+    /// @param {RMatcher} a - The matcher of the strings to keep
+    /// @param {RMatcher} b - The matcher of the strings to remove
+    /// @returns {RMatcher}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn difference(a: &RMatcher, b: &RMatcher) -> Result<RMatcher> {
+        let dfa = dfa::combine(
+            &a.dfa,
+            &b.dfa,
+            |a_live, _| a_live,
+            |a_accepts, b_accepts| a_accepts && !b_accepts,
+        )?;
+        Ok(RMatcher { dfa })
+    }
+
+    /// Returns a matcher of the strings not matched by `a`.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RMatcher } from "rregex"
+    ///
+    /// const matcher = RMatcher.complement(new RMatcher("(?s).*\\.tmp"))
+    /// expect(matcher.isMatch("notes.txt")).toBe(true)
+    /// expect(matcher.isMatch("notes.tmp")).toBe(false)
+    /// ```
+    ///
+    /// @param {RMatcher} a - The matcher to complement
+    /// @returns {RMatcher}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn complement(a: &RMatcher) -> Result<RMatcher> {
+        let dfa = dfa::combine(
+            &dfa::all_strings()?,
+            &a.dfa,
+            |all_live, _| all_live,
+            |all_accepts, a_accepts| all_accepts && !a_accepts,
+        )?;
+        Ok(RMatcher { dfa })
+    }
+
+    /// Returns true if and only if the whole text is matched.
+    ///
+    /// @param {string} text - The text to match
+    /// @returns {boolean}
+    #[wasm_bindgen(skip_jsdoc, js_name = isMatch)]
+    pub fn is_match(&self, text: &str) -> bool {
+        dfa::accepts(&self.dfa, text.as_bytes())
+    }
+
+    /// Serializes the DFA of the matcher, to store a combined matcher and
+    /// load it later with `RMatcher.deserialize` without compiling it again.
+    ///
+    /// The bytes use the little endian format of the dense DFAs of
+    /// `regex-automata`, so they can also be loaded in rust with
+    /// `regex_automata::dfa::dense::DFA::from_bytes`.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RMatcher } from "rregex"
+    ///
+    /// const bytes = RMatcher.complement(new RMatcher("a+")).serialize()
+    /// const matcher = RMatcher.deserialize(bytes)
+    /// expect(matcher.isMatch("b")).toBe(true)
+    /// expect(matcher.isMatch("aa")).toBe(false)
+    /// ```
+    ///
+    /// @see https://docs.rs/regex-automata/latest/regex_automata/dfa/dense/struct.DFA.html#method.to_bytes_little_endian
+    /// @returns {Uint8Array}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn serialize(&self) -> Vec<u8> {
+        dfa::to_bytes(&self.dfa)
+    }
+
+    /// Loads a matcher serialized with `serialize`. If the bytes are not a
+    /// valid DFA, or the DFA can't match whole strings, then an error is
+    /// thrown.
+    ///
+    /// @param {Uint8Array} bytes - The serialized DFA
+    /// @returns {RMatcher}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn deserialize(bytes: &[u8]) -> Result<RMatcher> {
+        let dfa = dfa::from_bytes(bytes)?;
+        dfa.start_state_forward(&Input::new("").anchored(Anchored::Yes))
+            .map_err(serde_wasm_bindgen::Error::new)?;
+        Ok(RMatcher { dfa })
+    }
+}
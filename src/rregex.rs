@@ -1,8 +1,38 @@
 use std::convert::TryInto;
 
+use crate::cache;
+use crate::dfa;
+use crate::dot;
+use crate::descriptor;
+use crate::descriptor::{DescriptorOptions, RegexDescriptor};
+use crate::dot::DotOptions;
+use crate::engine;
+use crate::engine::Engine;
+use crate::explain;
+use crate::explain::RExplanation;
+use crate::generate;
+use crate::generate::{EnumerateOptions, GenerateOptions};
+use crate::jsregexp;
+use crate::language;
+use crate::language::LanguageOptions;
+use crate::literals;
+use crate::literals::LiteralsOptions;
+use crate::options::{RegexEngine, RegexOptions};
+use crate::posix;
+use crate::profile;
+use crate::profile::Profile;
+use crate::sql;
+use crate::template;
+use crate::trie;
+use crate::trie::LiteralListOptions;
+use crate::types;
 use crate::types::Hir;
 use crate::types::Match;
-use regex_syntax::Parser;
+use regex::Replacer;
+use regex_automata::meta;
+use regex_automata::util::captures::Captures;
+use regex_automata::Input;
+use std::cell::OnceCell;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsValue;
 
@@ -50,6 +80,10 @@ type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
 #[wasm_bindgen]
 pub struct RRegex {
     regex: regex::Regex,
+    options: RegexOptions,
+    engine: Option<Engine>,
+    profile: Option<Profile>,
+    meta: OnceCell<meta::Regex>,
 }
 
 #[wasm_bindgen]
@@ -58,11 +92,17 @@ impl RRegex {
     /// to search, split or replace text in a string.
     ///
     /// If an invalid expression is given, then an error is returned.
-    #[wasm_bindgen(constructor)]
-    pub fn new(re: &str) -> Result<RRegex> {
-        let r = regex::Regex::new(re).map_err(serde_wasm_bindgen::Error::new)?;
-
-        Ok(RRegex { regex: r })
+    ///
+    /// The optional `options` configure the default flags and limits of the
+    /// expression, see `RegexOptions`.
+    ///
+    /// @see https://docs.rs/regex/latest/regex/struct.RegexBuilder.html
+    /// @param {string} re - The regular expression
+    /// @param {RegexOptions} options - The options used to compile the regular expression
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(re: &str, options: JsValue) -> Result<RRegex> {
+        let options: RegexOptions = types::options(options)?;
+        RRegex::with_options(re, options)
     }
 
     /// Returns true if and only if there is a match for the regex in the
@@ -88,8 +128,11 @@ impl RRegex {
     /// @param {string} text - The string against which to match the regular expression
     /// @return {boolean}
     #[wasm_bindgen(skip_jsdoc, js_name = isMatch)]
-    pub fn is_match(&self, text: &str) -> bool {
-        self.regex.is_match(text)
+    pub fn is_match(&self, text: &str) -> Result<bool> {
+        self.measure("isMatch", text.len(), || {
+            let matched = self.matches_at(text, 0)?;
+            Ok((matched, usize::from(matched)))
+        })
     }
 
     /// Returns the same as is_match, but starts the search at the given offset.
@@ -102,12 +145,11 @@ impl RRegex {
     /// @param {number} start - Zero-based index at which to start matching
     /// @return {boolean}
     #[wasm_bindgen(skip_jsdoc, js_name = isMatchAt)]
-    pub fn is_match_at(&self, text: &str, start: usize) -> bool {
-        if text.len() < start {
-            false
-        } else {
-            self.regex.is_match_at(text, start)
-        }
+    pub fn is_match_at(&self, text: &str, start: usize) -> Result<bool> {
+        self.measure("isMatchAt", text.len().saturating_sub(start), || {
+            let matched = self.matches_at(text, start)?;
+            Ok((matched, usize::from(matched)))
+        })
     }
 
     /// Returns the start and end byte range of the leftmost-first match in
@@ -136,7 +178,15 @@ impl RRegex {
     /// @return {Match}
     #[wasm_bindgen(skip_jsdoc)]
     pub fn find(&self, text: &str) -> Result<JsValue> {
-        self.find_at(text, 0)
+        let m = self.measure("find", text.len(), || {
+            let m = self.find_from(text, 0)?;
+            let count = usize::from(m.is_some());
+            Ok((m, count))
+        })?;
+        match m {
+            Some(m) => m.try_into(),
+            None => Ok(JsValue::UNDEFINED),
+        }
     }
 
     /// Returns the same as find, but starts the search at the given
@@ -152,14 +202,13 @@ impl RRegex {
     /// @returns {Match}
     #[wasm_bindgen(skip_jsdoc, js_name = findAt)]
     pub fn find_at(&self, text: &str, start: usize) -> Result<JsValue> {
-        if start > text.len() {
-            return Ok(JsValue::UNDEFINED);
-        };
-
-        let r = self.regex.find_at(text, start);
-
-        match r {
-            Some(m) => Match::from(m).try_into(),
+        let m = self.measure("findAt", text.len().saturating_sub(start), || {
+            let m = self.find_from(text, start)?;
+            let count = usize::from(m.is_some());
+            Ok((m, count))
+        })?;
+        match m {
+            Some(m) => m.try_into(),
             None => Ok(JsValue::UNDEFINED),
         }
     }
@@ -184,7 +233,21 @@ impl RRegex {
     /// @returns {Match}
     #[wasm_bindgen(skip_jsdoc, js_name = findAll)]
     pub fn find_all(&self, text: &str) -> Result<JsValue> {
-        let matches: Vec<Match> = self.regex.find_iter(text).map(Match::from).collect();
+        let matches = self.measure("findAll", text.len(), || {
+            let matches: Vec<Match> = match &self.engine {
+                Some(engine) => {
+                    let mut matches = Vec::new();
+                    engine.each_match(text, |m| {
+                        matches.push(Match::new(text, m.start(), m.end()));
+                        true
+                    })?;
+                    matches
+                }
+                None => self.regex.find_iter(text).map(Match::from).collect(),
+            };
+            let count = matches.len();
+            Ok((matches, count))
+        })?;
         serde_wasm_bindgen::to_value(&matches)
     }
 
@@ -250,11 +313,20 @@ impl RRegex {
     /// @returns {Captures|undefined}
     #[wasm_bindgen(skip_jsdoc)]
     pub fn captures(&self, text: &str) -> Result<JsValue> {
-        if let Some(captures) = self.regex.captures(text) {
-            Match::captures(captures, self.regex.capture_names())
-        } else {
-            Ok(JsValue::undefined())
-        }
+        self.measure("captures", text.len(), || {
+            let captures = match &self.engine {
+                Some(engine) => match engine.captures(text)? {
+                    Some(captures) => Match::automata_captures(text, &captures)?,
+                    None => JsValue::undefined(),
+                },
+                None => match self.regex.captures(text) {
+                    Some(captures) => Match::captures(captures, self.regex.capture_names())?,
+                    None => JsValue::undefined(),
+                },
+            };
+            let count = usize::from(!captures.is_undefined());
+            Ok((captures, count))
+        })
     }
 
     /// Returns a list with all the non-overlapping capture groups matched
@@ -288,14 +360,11 @@ impl RRegex {
     /// @returns {Captures[]}
     #[wasm_bindgen(skip_jsdoc, js_name = capturesAll)]
     pub fn captures_all(&self, text: &str) -> Result<JsValue> {
-        let names = self.regex.capture_names();
-        let result = js_sys::Array::new();
-        for captures in self.regex.captures_iter(text) {
-            let c = Match::captures(captures, names.clone())?;
-            result.push(&c);
-        }
-
-        Ok(JsValue::from(result))
+        self.measure("capturesAll", text.len(), || {
+            let result = self.captures_array(text)?;
+            let count = result.length() as usize;
+            Ok((JsValue::from(result), count))
+        })
     }
 
     /// Returns the number of captures.
@@ -407,8 +476,8 @@ impl RRegex {
     /// @param {string} rep - It's a string, it will replace the substring matched by `pattern`.
     /// @returns {string}
     #[wasm_bindgen(skip_jsdoc)]
-    pub fn replace(&self, text: &str, rep: &str) -> String {
-        self.regex.replace(text, rep).into_owned()
+    pub fn replace(&self, text: &str, rep: &str) -> Result<String> {
+        self.measure("replace", text.len(), || self.replace_counted(text, 1, rep))
     }
 
     /// Replaces at most `limit` non-overlapping matches in `text` with the
@@ -424,8 +493,10 @@ impl RRegex {
     /// @param {string} rep - It's a string, it will replace the substring matched by `pattern`.
     /// @returns {string}
     #[wasm_bindgen(skip_jsdoc)]
-    pub fn replacen(&self, text: &str, limit: usize, rep: &str) -> String {
-        self.regex.replacen(text, limit, rep).into_owned()
+    pub fn replacen(&self, text: &str, limit: usize, rep: &str) -> Result<String> {
+        self.measure("replacen", text.len(), || {
+            self.replace_counted(text, limit, rep)
+        })
     }
 
     /// Replaces all non-overlapping matches in `text` with the replacement
@@ -441,8 +512,10 @@ impl RRegex {
     /// @param {string} rep - It's a string, it will replace the substring matched by `pattern`.
     /// @returns {string}
     #[wasm_bindgen(skip_jsdoc, js_name = replaceAll)]
-    pub fn replace_all(&self, text: &str, rep: &str) -> String {
-        self.regex.replace_all(text, rep).into_owned()
+    pub fn replace_all(&self, text: &str, rep: &str) -> Result<String> {
+        self.measure("replaceAll", text.len(), || {
+            self.replace_counted(text, 0, rep)
+        })
     }
 
     /// Returns an iterator of substrings of `text` delimited by a match of the
@@ -467,8 +540,15 @@ impl RRegex {
     /// @param {string} text - The string against which to match the regular expression
     /// @returns {string[]}
     #[wasm_bindgen(skip_jsdoc)]
-    pub fn split(&self, text: &str) -> Vec<String> {
-        self.regex.split(text).map(|s| s.to_string()).collect()
+    pub fn split(&self, text: &str) -> Result<Vec<String>> {
+        self.measure("split", text.len(), || {
+            let substrings = match &self.engine {
+                Some(engine) => engine.splitn(text, None)?,
+                None => self.regex.split(text).map(|s| s.to_string()).collect(),
+            };
+            let count = substrings.len().saturating_sub(1);
+            Ok((substrings, count))
+        })
     }
 
     /// Returns an iterator of at most `limit` substrings of `text` delimited
@@ -495,11 +575,19 @@ impl RRegex {
     /// @param {number} limit - Max number result elements
     /// @returns {string[]}
     #[wasm_bindgen(skip_jsdoc)]
-    pub fn splitn(&self, text: &str, limit: usize) -> Vec<String> {
-        self.regex
-            .splitn(text, limit)
-            .map(|s| s.to_string())
-            .collect()
+    pub fn splitn(&self, text: &str, limit: usize) -> Result<Vec<String>> {
+        self.measure("splitn", text.len(), || {
+            let substrings = match &self.engine {
+                Some(engine) => engine.splitn(text, Some(limit))?,
+                None => self
+                    .regex
+                    .splitn(text, limit)
+                    .map(|s| s.to_string())
+                    .collect(),
+            };
+            let count = substrings.len().saturating_sub(1);
+            Ok((substrings, count))
+        })
     }
 
     /// Returns the end location of a match in the text given.
@@ -534,8 +622,11 @@ impl RRegex {
     /// @param {string} text - The string against which to match the regular expression
     /// @returns {number|undefined}
     #[wasm_bindgen(skip_jsdoc, js_name = shortestMatch)]
-    pub fn shortest_match(&self, text: &str) -> Option<usize> {
-        self.regex.shortest_match(text)
+    pub fn shortest_match(&self, text: &str) -> Result<Option<usize>> {
+        self.measure("shortestMatch", text.len(), || {
+            let end = self.shortest_from(text, 0)?;
+            Ok((end, usize::from(end.is_some())))
+        })
     }
 
     /// Returns the same as `shortest_match`, but starts the search at the
@@ -549,18 +640,17 @@ impl RRegex {
     /// @param {number} start - Zero-based index at which to start matching
     /// @returns {number|undefined}
     #[wasm_bindgen(skip_jsdoc, js_name = shortestMatchAt)]
-    pub fn shortest_match_at(&self, text: &str, start: usize) -> Option<usize> {
-        if text.len() < start {
-            None
-        } else {
-            self.regex.shortest_match_at(text, start)
-        }
+    pub fn shortest_match_at(&self, text: &str, start: usize) -> Result<Option<usize>> {
+        self.measure("shortestMatchAt", text.len().saturating_sub(start), || {
+            let end = self.shortest_from(text, start)?;
+            Ok((end, usize::from(end.is_some())))
+        })
     }
 
     /// Returns the regular expression into a high level intermediate
     /// representation.
     pub fn syntax(&self) -> Result<JsValue> {
-        let mut parser = Parser::new();
+        let mut parser = self.options.parser();
         let hir = parser
             .parse(self.regex.as_str())
             .map_err(serde_wasm_bindgen::Error::new)?;
@@ -568,9 +658,938 @@ impl RRegex {
         Hir::from(&hir).try_into()
     }
 
+    /// Explains the regular expression in plain English, as a tree with a
+    /// node for each part of the pattern.
+    ///
+    /// Every node has a description (e.g. `exactly 5 times` or `one
+    /// character from: "0" to "9" or "a" to "f"`), the span of the pattern it
+    /// explains, and the nodes it's made of. Consecutive characters are
+    /// explained together as a text, and flags like `(?i)` or the
+    /// `multiLine` option change the descriptions of the nodes they apply
+    /// to.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const explanation = new RRegex("^(?<year>\\d{4})-").explain()
+    /// expect(explanation.toText()).toBe([
+    ///   "in this order:",
+    ///   "  the start of the text",
+    ///   '  capture group 1 named "year":',
+    ///   "    exactly 4 times:",
+    ///   "      a digit",
+    ///   '  the character "-"',
+    /// ].join("\n"))
+    /// expect(explanation.tree().children[1].span.start.offset).toBe(1)
+    /// ```
+    ///
+    /// @returns {RExplanation}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn explain(&self) -> Result<RExplanation> {
+        Ok(explain::explain(self.regex.as_str(), &self.options)?)
+    }
+
+    /// Generates random strings matched by the regular expression as a
+    /// whole, e.g. to fuzz a parser or seed test fixtures.
+    ///
+    /// Strings are generated from the high level intermediate
+    /// representation, so every alternative, repetition and character of a
+    /// class (including Unicode classes like `\p{Greek}`) can be chosen.
+    /// Unbounded repetitions are repeated at most `maxRepeat` times beyond
+    /// their minimum. The same seed always generates the same strings.
+    ///
+    /// Assertions like `\b` are not taken into account while generating,
+    /// strings that don't satisfy them are discarded and generated again, so
+    /// fewer strings than requested may be returned when the assertions are
+    /// rarely satisfied or the pattern can't match anything.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const re = new RRegex("[a-f0-9]{8}-(draft|final)")
+    /// const strings = re.generate({ count: 3, seed: 42 })
+    /// expect(strings.length).toBe(3)
+    /// expect(strings.every((text) => /^[a-f0-9]{8}-(draft|final)$/.test(text))).toBe(true)
+    /// expect(re.generate({ count: 3, seed: 42 })).toEqual(strings)
+    /// ```
+    ///
+    /// @param {GenerateOptions} options - The number of strings, the seed and the repetition limit
+    /// @returns {string[]}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn generate(&self, options: JsValue) -> Result<Vec<String>> {
+        let options: GenerateOptions = types::options(options)?;
+        generate::generate(&self.hir()?, &options)
+    }
+
+    /// Lists the strings matched by the regular expression as a whole, in
+    /// shortlex order: shorter strings (in characters) first, and strings of
+    /// the same length sorted by their code points.
+    ///
+    /// The whole language of a finite pattern is listed when it has at most
+    /// `limit` strings, otherwise only the first `limit` strings are
+    /// returned.
+    ///
+    /// The pattern is compiled into a DFA, which doesn't support Unicode
+    /// word boundaries, use `(?-u:\b)` instead.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const re = new RRegex("(ab|b)c?")
+    /// expect(re.enumerate()).toEqual(["b", "ab", "bc", "abc"])
+    /// expect(new RRegex("a*").enumerate({ limit: 3 })).toEqual(["", "a", "aa"])
+    /// ```
+    ///
+    /// @param {EnumerateOptions} options - The maximum number of strings
+    /// @returns {string[]}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn enumerate(&self, options: JsValue) -> Result<Vec<String>> {
+        let options: EnumerateOptions = types::options(options)?;
+        generate::enumerate(&self.hir()?, &options, self.options.dfa_size_limit)
+    }
+
+    /// Returns whether every string the regular expression matches, like
+    /// `isMatch` does, is also matched by `other`. If not, the shortest
+    /// string matched only by this regular expression is returned as the
+    /// `example`. With `whole: true`, only the strings matched as a whole
+    /// are compared, e.g. to compare validators.
+    ///
+    /// Both patterns are compiled into DFAs and their product is searched,
+    /// so the check is exact (it doesn't sample strings), but Unicode word
+    /// boundaries are not supported, use `(?-u:\b)` instead. Throws when a
+    /// DFA is larger than `dfaSizeLimit`, 10 MB by default.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const strict = new RRegex("^[0-9]{3}-[0-9]{4}$")
+    /// const loose = new RRegex("^[0-9-]+$")
+    /// expect(strict.isSubsetOf(loose)).toEqual({ result: true })
+    /// expect(loose.isSubsetOf(strict)).toEqual({ result: false, example: "-" })
+    /// expect(new RRegex("ab").isSubsetOf(new RRegex("b"))).toEqual({ result: true })
+    /// expect(new RRegex("ab").isSubsetOf(new RRegex("b"), { whole: true })).toEqual({
+    ///   result: false,
+    ///   example: "ab",
+    /// })
+    /// ```
+    ///
+    /// @param {RRegex} other - The regular expression that should match a superset
+    /// @param {LanguageOptions} options - Whether the strings are matched as a whole
+    /// @returns {LanguageCheck}
+    #[wasm_bindgen(skip_jsdoc, js_name = isSubsetOf)]
+    pub fn is_subset_of(&self, other: &RRegex, options: JsValue) -> Result<JsValue> {
+        let options: LanguageOptions = types::options(options)?;
+        let check = language::is_subset(&self.dfa(&options)?, &other.dfa(&options)?);
+        serde_wasm_bindgen::to_value(&check)
+    }
+
+    /// Returns whether the regular expression and `other` match exactly the
+    /// same strings, e.g. to prove that a refactored pattern didn't change.
+    /// If not, the shortest string matched by only one of them is returned
+    /// as the `example`. See `isSubsetOf` for the options.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const re = new RRegex("^(a|b)*c")
+    /// expect(re.isEquivalent(new RRegex("^[ab]*c"))).toEqual({ result: true })
+    /// expect(re.isEquivalent(new RRegex("^[ab]+c"))).toEqual({ result: false, example: "c" })
+    /// expect(new RRegex("a").isEquivalent(new RRegex("^a$")).result).toBe(false)
+    /// ```
+    ///
+    /// @param {RRegex} other - The regular expression to compare with
+    /// @param {LanguageOptions} options - Whether the strings are matched as a whole
+    /// @returns {LanguageCheck}
+    #[wasm_bindgen(skip_jsdoc, js_name = isEquivalent)]
+    pub fn is_equivalent(&self, other: &RRegex, options: JsValue) -> Result<JsValue> {
+        let options: LanguageOptions = types::options(options)?;
+        let check = language::is_equivalent(&self.dfa(&options)?, &other.dfa(&options)?);
+        serde_wasm_bindgen::to_value(&check)
+    }
+
+    /// Returns whether some string is matched by both the regular expression
+    /// and `other`, with the shortest one as the `example`. See `isSubsetOf`
+    /// for the options.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const re = new RRegex("^[a-z]+[0-9]$")
+    /// expect(re.intersects(new RRegex("^x+[0-9]+$"))).toEqual({ result: true, example: "x0" })
+    /// expect(re.intersects(new RRegex("^[0-9]+$"))).toEqual({ result: false })
+    /// ```
+    ///
+    /// @param {RRegex} other - The regular expression to compare with
+    /// @param {LanguageOptions} options - Whether the strings are matched as a whole
+    /// @returns {LanguageCheck}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn intersects(&self, other: &RRegex, options: JsValue) -> Result<JsValue> {
+        let options: LanguageOptions = types::options(options)?;
+        let check = language::intersects(&self.dfa(&options)?, &other.dfa(&options)?);
+        serde_wasm_bindgen::to_value(&check)
+    }
+
+    /// Renders the automaton the regular expression is compiled into as a
+    /// Graphviz DOT digraph, e.g. to find out why a pattern compiles into an
+    /// enormous automaton.
+    ///
+    /// The Thompson NFA (`automaton: "nfa"`, the default) is rendered with
+    /// all its states, where dashed edges don't consume any input and are
+    /// labeled with the assertion or capture group they check. The dense DFA
+    /// (`"dfa"`), optionally minimized (`"minimal-dfa"`), is rendered from
+    /// its start state at the beginning of the text, without the dead
+    /// state. Edges are labeled with the byte ranges they read, and match
+    /// states are drawn as double circles listing the patterns they match.
+    ///
+    /// Note that the match states of a DFA are delayed by one byte, so the
+    /// text matched before reaching them doesn't include the last byte, and
+    /// that DFAs don't support Unicode word boundaries, use `(?-u:\b)`
+    /// instead.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const dot = new RRegex("^a[0-9]+$").toDot({ automaton: "minimal-dfa" })
+    /// expect(dot.startsWith("digraph {")).toBe(true)
+    /// expect(dot).toContain('label="0-9"')
+    /// expect(dot).toContain("shape=doublecircle")
+    /// ```
+    ///
+    /// @param {DotOptions} options - The automaton to render
+    /// @returns {string}
+    #[wasm_bindgen(skip_jsdoc, js_name = toDot)]
+    pub fn to_dot(&self, options: JsValue) -> Result<String> {
+        let options: DotOptions = types::options(options)?;
+        dot::to_dot(&[self.hir()?], &options, &self.options)
+    }
+
+    /// Describes how the regular expression was compiled: the engines that
+    /// were built (see the `engine` option), the number of states of the
+    /// NFA, the memory used in bytes and the prefilter used to find the
+    /// candidates of a match, if any.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const info = new RRegex("(foo|bar)\\s*[0-9]+", { engine: "hybrid" }).info()
+    /// expect(info.engines).toEqual(["hybrid", "pikevm"])
+    /// expect(info.prefilter?.literals).toEqual(["foo", "bar"])
+    /// ```
+    ///
+    /// @returns {RegexInfo}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn info(&self) -> Result<JsValue> {
+        let info = match &self.engine {
+            Some(engine) => engine.info(),
+            None => engine::auto_info(&self.hir()?, &self.options)?,
+        };
+        serde_wasm_bindgen::to_value(&info)
+    }
+
+    /// Returns the counters recorded by the search methods (`isMatch`,
+    /// `find`, `captures`, `replace`, `split`, ...) by method name, when
+    /// compiled with `profile: true`: the number of calls, the bytes of the
+    /// haystacks, the matches found, the clears of the lazy DFA cache and
+    /// the time spent. Without profiling, the stats are always empty.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const re = new RRegex("\\d+", { profile: true })
+    /// re.findAll("1 22 333")
+    /// re.isMatch("none")
+    /// const { findAll, isMatch } = re.stats()
+    /// expect(findAll.calls).toBe(1)
+    /// expect(findAll.bytesScanned).toBe(8)
+    /// expect(findAll.matches).toBe(3)
+    /// expect(isMatch.matches).toBe(0)
+    /// ```
+    ///
+    /// @returns {RegexStats}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn stats(&self) -> Result<JsValue> {
+        profile::stats(self.profile.as_ref())
+    }
+
+    /// Clears the counters returned by `stats`.
+    #[wasm_bindgen(js_name = resetStats)]
+    pub fn reset_stats(&self) {
+        if let Some(profile) = &self.profile {
+            profile.reset();
+        }
+    }
+
+    /// Returns the sequence of literals that every match of the regular
+    /// expression must start with (`kind: "prefix"`, the default) or end
+    /// with (`kind: "suffix"`).
+    ///
+    /// The sequence is useful to build cheap pre-filters, like a `LIKE`
+    /// clause in a database query or the keys of a bloom filter. Each literal
+    /// reports whether it is exact (a complete match) or inexact (only a
+    /// prefix or suffix of a match).
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const re = new RRegex("(foo|bar)baz+")
+    /// const seq = re.literals({ kind: "prefix" })
+    /// expect(seq.finite).toBe(true)
+    /// expect(seq.literals).toEqual([
+    ///   { value: "foobaz", bytes: new Uint8Array([102, 111, 111, 98, 97, 122]), exact: false },
+    ///   { value: "barbaz", bytes: new Uint8Array([98, 97, 114, 98, 97, 122]), exact: false },
+    /// ])
+    /// ```
+    ///
+    /// @see https://docs.rs/regex-syntax/latest/regex_syntax/hir/literal/struct.Extractor.html
+    /// @param {LiteralsOptions} options - The kind of literals and the limits used to extract them
+    /// @returns {LiteralSeq}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn literals(&self, options: JsValue) -> Result<JsValue> {
+        let options: LiteralsOptions = types::options(options)?;
+        literals::extract(&mut self.options.parser(), self.regex.as_str(), &options)
+    }
+
+    /// Translates a pattern in the rust syntax into an equivalent javascript
+    /// `RegExp`, returning its `source` and `flags`.
+    ///
+    /// The translation is done over the high level intermediate
+    /// representation, so `(?i)` scopes, Unicode classes like `\p{Greek}` and
+    /// flags like `(?m)` or `(?s)` are expanded into explicit ranges and
+    /// look-around assertions that behave exactly like the original pattern.
+    /// Unicode word boundaries are written with `\p{...}` classes, so they
+    /// depend on the Unicode tables of the javascript engine. The resulting
+    /// expression always requires the `u` flag.
+    ///
+    /// If the pattern uses a construct with no faithful equivalent in
+    /// javascript (e.g. capture names with `.` or `[`), then a `PatternError`
+    /// naming the span of the construct is thrown.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const { source, flags } = RRegex.toJsRegExp("(?i)a+\\z")
+    /// expect(source).toBe("[Aa]+$")
+    /// expect(new RegExp(source, flags).test("xAa")).toBe(true)
+    /// ```
+    ///
+    /// @param {string} pattern - A regular expression in the rust syntax
+    /// @returns {JsRegExp}
+    #[wasm_bindgen(skip_jsdoc, js_name = toJsRegExp)]
+    pub fn to_js_regexp(pattern: &str) -> Result<JsValue> {
+        let regexp = jsregexp::to_js_regexp(pattern)?;
+        serde_wasm_bindgen::to_value(&regexp)
+    }
+
+    /// Compiles the source and flags of a javascript `RegExp`, or a `RegExp`
+    /// object itself, into an equivalent regular expression.
+    ///
+    /// The pattern is parsed with the ECMAScript syntax and rewritten into
+    /// the rust syntax, so `\d`, `\w`, `\s`, `\b` and `.` keep their
+    /// javascript meaning (e.g. `\d` is ASCII only and `.` doesn't match
+    /// `\r`), `(?<name>...)` groups keep their names and indices, and escapes
+    /// like `\u{1F600}`, `😀`, `\cJ` or legacy octals are decoded.
+    ///
+    /// The flags map to `RegexOptions`: `i` to `caseInsensitive` and `s` to
+    /// `dotMatchesNewLine`. Without the `u` and `v` flags, javascript
+    /// compares characters by `toUpperCase` rather than by case folding, so
+    /// `i` is written as classes instead, e.g. `s` becomes `[Ss]` and doesn't
+    /// match `ſ`. `u` and `v` select the parsing mode, and `g` and `d` are
+    /// ignored, since the kind of search depends on the method called (e.g.
+    /// `find` vs `findAll`).
+    ///
+    /// Backreferences, lookahead and lookbehind assertions and strings in
+    /// classes (`\q{...}`) have no equivalent, nor have `^` and `$` in
+    /// multi-line mode, where javascript also ends lines at `\r`, `\u2028`
+    /// and `\u2029`, and sticky searches (`y`), so a `PatternError` naming
+    /// their span is thrown instead. Invalid patterns and flags throw a
+    /// `PatternError` of kind `Syntax`, with a span in the source or the
+    /// flags respectively.
+    ///
+    /// One difference remains: haystacks are matched by code points, even
+    /// without the `u` flag.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const re = RRegex.fromJsRegExp(/^(?<year>\d{4})-(?<month>\d{2})$/)
+    /// expect(re.toString()).toBe("^(?<year>[0-9]{4})-(?<month>[0-9]{2})$")
+    /// expect(re.captures("2024-05").name.year.value).toBe("2024")
+    /// ```
+    ///
+    /// @param {string | RegExp} source - The source of a javascript `RegExp`, or the `RegExp` itself
+    /// @param {string} flags - The flags of the `RegExp`, defaults to the flags of `source` when it's a `RegExp`
+    /// @returns {RRegex}
+    #[wasm_bindgen(skip_jsdoc, js_name = fromJsRegExp)]
+    pub fn from_js_regexp(source: JsValue, flags: Option<String>) -> Result<RRegex> {
+        let (source, flags) = match source.dyn_ref::<js_sys::RegExp>() {
+            Some(regexp) => (
+                String::from(regexp.source()),
+                flags.unwrap_or_else(|| String::from(regexp.flags())),
+            ),
+            None => {
+                let source = source
+                    .as_string()
+                    .ok_or_else(|| serde_wasm_bindgen::Error::new("source is not a string or a RegExp"))?;
+                (source, flags.unwrap_or_default())
+            }
+        };
+
+        let (pattern, options) = jsregexp::from_js_regexp(&source, &flags)?;
+        RRegex::with_options(&pattern, options)
+    }
+
+    /// Compiles a POSIX basic (`"bre"`) or extended (`"ere"`) regular
+    /// expression, as used by `grep`, `sed` and `awk`.
+    ///
+    /// Bracket expressions with classes like `[[:alpha:]]`, collating
+    /// symbols (`[[.-.]]`) and equivalence classes (`[[=a=]]`) are
+    /// supported, classes use the POSIX locale. In a basic expression groups
+    /// and intervals are written `\(...\)` and `\{m,n\}`, `^` and `$` are
+    /// only anchors at the start and end of a branch, and a leading `*` is
+    /// literal.
+    ///
+    /// Backreferences and GNU extensions like `\w` or `\<` have no
+    /// equivalent, so a `PatternError` of kind `Unsupported` naming their
+    /// span is thrown instead. Invalid patterns throw a `PatternError` of
+    /// kind `Syntax`, with a span in the original pattern.
+    ///
+    /// Note that POSIX specifies leftmost-longest matching, while the
+    /// compiled regular expression uses leftmost-first semantics like perl:
+    /// `a|ab` finds `a` in `ab`, where `grep -o` would find `ab`. Whole
+    /// string matches are not affected.
+    ///
+    /// Only the `caseInsensitive` and size limit options are used, the
+    /// translated pattern doesn't depend on the other ones.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const re = RRegex.fromPosix("^\\([[:alpha:]_][[:alnum:]_]*\\) *=", "bre")
+    /// expect(re.captures("name = value").get[1].value).toBe("name")
+    /// ```
+    ///
+    /// @param {string} pattern - A POSIX regular expression
+    /// @param {"bre" | "ere"} syntax - Whether the pattern is a basic or an extended regular expression
+    /// @param {RegexOptions} options - The options used to compile the translated pattern
+    /// @returns {RRegex}
+    #[wasm_bindgen(skip_jsdoc, js_name = fromPosix)]
+    pub fn from_posix(pattern: &str, syntax: &str, options: JsValue) -> Result<RRegex> {
+        let flavor = match syntax {
+            "bre" => posix::Flavor::Basic,
+            "ere" => posix::Flavor::Extended,
+            _ => {
+                return Err(serde_wasm_bindgen::Error::new(format!(
+                    "unknown POSIX syntax '{}', expected 'bre' or 'ere'",
+                    syntax
+                )))
+            }
+        };
+        let hir = posix::parse(pattern, flavor)?;
+        RRegex::from_hir(&hir, options)
+    }
+
+    /// Compiles a SQL `LIKE` pattern, where `%` matches any sequence of
+    /// characters and `_` matches any single character. The pattern always
+    /// matches the whole string, use the `caseInsensitive` option for
+    /// `ILIKE`.
+    ///
+    /// The escape character makes the next `%`, `_` or escape character
+    /// literal, it's not set by default (like in the SQL standard) and any
+    /// other character following it throws a `PatternError`.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const re = RRegex.fromSqlLike("50!%_off%", "!")
+    /// expect(re.isMatch("50% off today")).toBe(true)
+    /// expect(re.isMatch("50 % off")).toBe(false)
+    /// ```
+    ///
+    /// @param {string} pattern - A SQL `LIKE` pattern
+    /// @param {string} escape - The escape character, as in `LIKE pattern ESCAPE '!'`
+    /// @param {RegexOptions} options - The options used to compile the translated pattern
+    /// @returns {RRegex}
+    #[wasm_bindgen(skip_jsdoc, js_name = fromSqlLike)]
+    pub fn from_sql_like(pattern: &str, escape: Option<String>, options: JsValue) -> Result<RRegex> {
+        let escape = sql::escape_char(escape.as_deref())?;
+        let hir = sql::like(pattern, escape)?;
+        RRegex::from_hir(&hir, options)
+    }
+
+    /// Compiles a SQL `SIMILAR TO` pattern, which has the operators of a
+    /// POSIX extended regular expression (`|`, `*`, `+`, `?`, `{m,n}`,
+    /// groups and bracket expressions) along with the `%` and `_` wildcards
+    /// of `LIKE`. `.`, `^` and `$` are literal characters, and the pattern
+    /// always matches the whole string.
+    ///
+    /// The escape character makes the next character literal, it's not set
+    /// by default.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const re = RRegex.fromSqlSimilarTo("(INV|ORD)-[0-9]{4}%")
+    /// expect(re.isMatch("INV-2024 paid")).toBe(true)
+    /// expect(re.isMatch("XINV-2024")).toBe(false)
+    /// ```
+    ///
+    /// @param {string} pattern - A SQL `SIMILAR TO` pattern
+    /// @param {string} escape - The escape character, as in `SIMILAR TO pattern ESCAPE '!'`
+    /// @param {RegexOptions} options - The options used to compile the translated pattern
+    /// @returns {RRegex}
+    #[wasm_bindgen(skip_jsdoc, js_name = fromSqlSimilarTo)]
+    pub fn from_sql_similar_to(
+        pattern: &str,
+        escape: Option<String>,
+        options: JsValue,
+    ) -> Result<RRegex> {
+        let escape = sql::escape_char(escape.as_deref())?;
+        let hir = sql::similar(pattern, escape)?;
+        RRegex::from_hir(&hir, options)
+    }
+
+    /// Compiles a regular expression matching any of `words` literally,
+    /// written from a prefix tree of them instead of as the alternation of
+    /// every word, so common prefixes are only compiled once. When a word is
+    /// a prefix of another, the longest one is matched.
+    ///
+    /// Lists of tens of thousands of words may exceed the default
+    /// `sizeLimit`, which can be raised in the options.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const re = RRegex.fromLiterals(["foo", "foobar", "bar"], { wordBoundary: true })
+    /// expect(re.findAll("foobar bar foos").map((m) => m.value)).toEqual(["foobar", "bar"])
+    /// ```
+    ///
+    /// @param {string[]} words - The words to match
+    /// @param {LiteralListOptions} options - The options used to compile the words
+    /// @returns {RRegex}
+    #[wasm_bindgen(skip_jsdoc, js_name = fromLiterals)]
+    pub fn from_literals(words: Vec<String>, options: JsValue) -> Result<RRegex> {
+        let list: LiteralListOptions = types::options(options.clone())?;
+        RRegex::from_translated(&trie::pattern(&words, &list), options)
+    }
+
+    /// Returns the pattern compiled by `fromLiterals` for `words`, with
+    /// their common prefixes factored out.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// expect(RRegex.literalsToPattern(["foo", "foobar", "fizz"])).toBe("f(?:izz|oo(?:bar)?)")
+    /// ```
+    ///
+    /// @param {string[]} words - The words to match
+    /// @returns {string}
+    #[wasm_bindgen(skip_jsdoc, js_name = literalsToPattern)]
+    pub fn literals_to_pattern(words: Vec<String>) -> String {
+        trie::pattern(&words, &LiteralListOptions::default())
+    }
+
+    /// Compiles a regular expression like the constructor, reusing the
+    /// compiled pattern when the same pattern was already compiled with the
+    /// same options, see `RRegexCache`.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex, RRegexCache } from "rregex"
+    ///
+    /// function handle(pattern: string, text: string) {
+    ///   return RRegex.cached(pattern).isMatch(text)
+    /// }
+    /// handle("^/api/", "/api/users")
+    /// handle("^/api/", "/api/posts")
+    /// expect(RRegexCache.stats().hits).toBeGreaterThan(0)
+    /// ```
+    ///
+    /// @param {string} pattern - The regular expression
+    /// @param {RegexOptions} options - The options used to compile the regular expression
+    /// @returns {RRegex}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn cached(pattern: &str, options: JsValue) -> Result<RRegex> {
+        let options: RegexOptions = types::options(options)?;
+        let regex = cache::regex(pattern, &options)?;
+        RRegex::from_regex(regex, options)
+    }
+
+    /// Returns a plain object with the pattern and the options of the
+    /// regular expression, which can be sent to a worker with `postMessage`
+    /// and compiled again there with `RRegex.fromDescriptor`.
+    ///
+    /// With `automaton: true`, a regular expression compiled with
+    /// `engine: "dfa"` also includes its DFAs, so they are loaded instead of
+    /// built again. Other engines are quick to build, so they have no
+    /// automaton to include, and an error is thrown.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const re = new RRegex("[a-z]+@[a-z]+\\.com", { engine: "dfa" })
+    /// worker.postMessage(re.toDescriptor({ automaton: true }))
+    ///
+    /// // in the worker
+    /// onmessage = (event) => {
+    ///   const re = RRegex.fromDescriptor(event.data)
+    ///   expect(re.isMatch("hi@example.com")).toBe(true)
+    /// }
+    /// ```
+    ///
+    /// @param {DescriptorOptions} options - Whether to include the automaton
+    /// @returns {RegexDescriptor}
+    #[wasm_bindgen(skip_jsdoc, js_name = toDescriptor)]
+    pub fn to_descriptor(&self, options: JsValue) -> Result<JsValue> {
+        let options: DescriptorOptions = types::options(options)?;
+        let automaton = if options.automaton {
+            let bytes = self.engine.as_ref().and_then(Engine::dfa_bytes);
+            if bytes.is_none() {
+                return Err(serde_wasm_bindgen::Error::new(
+                    "only a regular expression compiled with the dfa engine has an automaton",
+                ));
+            }
+            bytes
+        } else {
+            None
+        };
+        let descriptor = RegexDescriptor {
+            pattern: self.regex.as_str().to_owned(),
+            options: self.options.clone(),
+        };
+        descriptor::to_value(&descriptor, automaton)
+    }
+
+    /// Compiles a regular expression described by `toDescriptor`. When the
+    /// descriptor includes an automaton, its DFAs are loaded instead of
+    /// built, which requires the `dfa` engine in the options, and the
+    /// automaton to come from the same version of this library.
+    ///
+    /// @param {RegexDescriptor} descriptor - The descriptor returned by `toDescriptor`
+    /// @returns {RRegex}
+    #[wasm_bindgen(skip_jsdoc, js_name = fromDescriptor)]
+    pub fn from_descriptor(descriptor: JsValue) -> Result<RRegex> {
+        let automaton = descriptor::automaton(&descriptor)?;
+        let RegexDescriptor { pattern, options } = serde_wasm_bindgen::from_value(descriptor)?;
+        let regex = options
+            .regex_builder(&pattern)
+            .build()
+            .map_err(serde_wasm_bindgen::Error::new)?;
+        match automaton {
+            Some((forward, reverse)) => {
+                if options.engine != RegexEngine::Dfa {
+                    return Err(serde_wasm_bindgen::Error::new(
+                        "an automaton can only be loaded with the dfa engine",
+                    ));
+                }
+                let hir = options
+                    .parser()
+                    .parse(&pattern)
+                    .map_err(serde_wasm_bindgen::Error::new)?;
+                let engine = Engine::from_dfa_bytes(&hir, &options, &forward, &reverse)?;
+                Ok(RRegex::from_engine(regex, options, Some(engine)))
+            }
+            None => RRegex::from_regex(regex, options),
+        }
+    }
+
+    /// Compiles a regular expression from a tagged template, where the
+    /// template is the pattern and the interpolated values are:
+    ///
+    /// - strings and numbers, matched literally, like with `escape`, in a
+    ///   non-capturing group so a quantifier after them repeats the whole
+    ///   value;
+    /// - arrays of strings and numbers, matching any of their items
+    ///   literally, the first ones being preferred;
+    /// - `RRegex` instances, embedded in a non-capturing group with the
+    ///   options they were compiled with, their capture groups being
+    ///   numbered after the ones before them. A group named like a group of
+    ///   an earlier `RRegex` is renamed with a `_2`, `_3`... suffix, so the
+    ///   same `RRegex` can be embedded twice.
+    ///
+    /// The raw strings of the template are used, so escapes like `\d` are
+    /// written as in a pattern.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegex } from "rregex"
+    ///
+    /// const user = "john.doe"
+    /// const year = new RRegex("(?P<year>\\d{4})")
+    /// const re = RRegex.fromTemplate`^${user}@${["example.com", "example.org"]} ${year}$`
+    /// expect(re.isMatch("john.doe@example.org 2024")).toBe(true)
+    /// expect(re.isMatch("johnXdoe@example.org 2024")).toBe(false)
+    /// ```
+    ///
+    /// @param {TemplateStringsArray | string[]} strings - The pattern around the values
+    /// @param {TemplateValue[]} values - The interpolated values
+    /// @returns {RRegex}
+    #[wasm_bindgen(skip_jsdoc, js_name = fromTemplate, variadic)]
+    pub fn from_template(strings: JsValue, values: Vec<JsValue>) -> Result<RRegex> {
+        let pattern = template::pattern(&strings, &values)?;
+        RRegex::with_options(&pattern, RegexOptions::default())
+    }
+
     /// Returns a string representing the regular expression
     #[wasm_bindgen(js_name = toString)]
     pub fn as_string(&self) -> String {
         self.regex.as_str().to_owned()
     }
 }
+
+impl RRegex {
+    pub fn with_options(re: &str, options: RegexOptions) -> Result<RRegex> {
+        let regex = options
+            .regex_builder(re)
+            .build()
+            .map_err(serde_wasm_bindgen::Error::new)?;
+        RRegex::from_regex(regex, options)
+    }
+
+    /// Wraps a regular expression compiled with `options`, building the
+    /// engine they select.
+    pub fn from_regex(regex: regex::Regex, options: RegexOptions) -> Result<RRegex> {
+        let engine = if options.engine.is_auto() {
+            None
+        } else {
+            let hir = options
+                .parser()
+                .parse(regex.as_str())
+                .map_err(serde_wasm_bindgen::Error::new)?;
+            Some(Engine::new(&hir, options.engine, &options)?)
+        };
+        Ok(RRegex::from_engine(regex, options, engine))
+    }
+
+    fn from_engine(regex: regex::Regex, options: RegexOptions, engine: Option<Engine>) -> RRegex {
+        let profile = if options.profile {
+            Some(Profile::default())
+        } else {
+            None
+        };
+
+        RRegex {
+            regex,
+            options,
+            engine,
+            profile,
+            meta: OnceCell::new(),
+        }
+    }
+
+    /// Finds the leftmost-first match in `input` and its capture groups,
+    /// e.g. for anchored searches, which the regex crate doesn't expose.
+    /// With the `auto` engine, `input` is searched by the meta regex of
+    /// `regex-automata` the regex crate is built on, compiled on first use.
+    pub fn search_captures(&self, input: &Input) -> Result<Captures> {
+        if let Some(engine) = &self.engine {
+            let mut captures = engine.create_captures();
+            engine
+                .search_captures(input, &mut captures)
+                .map_err(serde_wasm_bindgen::Error::new)?;
+            return Ok(captures);
+        }
+
+        let regex = match self.meta.get() {
+            Some(regex) => regex,
+            None => {
+                let mut config = meta::Config::new();
+                if let Some(limit) = self.options.size_limit {
+                    config = config.nfa_size_limit(Some(limit));
+                }
+                if let Some(limit) = self.options.dfa_size_limit {
+                    config = config.hybrid_cache_capacity(limit);
+                }
+                let regex = meta::Regex::builder()
+                    .configure(config)
+                    .build_from_hir(&self.hir()?)
+                    .map_err(serde_wasm_bindgen::Error::new)?;
+                self.meta.get_or_init(|| regex)
+            }
+        };
+        let mut captures = regex.create_captures();
+        regex.search_captures(input, &mut captures);
+        Ok(captures)
+    }
+
+    /// Runs a search method, recording it when profiling. `search` returns
+    /// its result with the number of matches it found.
+    fn measure<T, F>(&self, method: &'static str, bytes: usize, search: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<(T, usize)>,
+    {
+        profile::measure(
+            self.profile.as_ref(),
+            method,
+            bytes,
+            || self.engine.as_ref().map_or(0, Engine::cache_clears),
+            search,
+        )
+    }
+
+    fn matches_at(&self, text: &str, start: usize) -> Result<bool> {
+        Ok(self.shortest_from(text, start)?.is_some())
+    }
+
+    fn find_from<'t>(&self, text: &'t str, start: usize) -> Result<Option<Match<'t>>> {
+        if start > text.len() {
+            return Ok(None);
+        }
+        match &self.engine {
+            Some(engine) => Ok(engine
+                .find_at(text, start)?
+                .map(|m| Match::new(text, m.start(), m.end()))),
+            None => Ok(self.regex.find_at(text, start).map(Match::from)),
+        }
+    }
+
+    fn shortest_from(&self, text: &str, start: usize) -> Result<Option<usize>> {
+        if text.len() < start {
+            Ok(None)
+        } else if let Some(engine) = &self.engine {
+            engine.shortest_match_at(text, start)
+        } else {
+            Ok(self.regex.shortest_match_at(text, start))
+        }
+    }
+
+    fn captures_array(&self, text: &str) -> Result<js_sys::Array> {
+        let result = js_sys::Array::new();
+        if let Some(engine) = &self.engine {
+            let mut error = None;
+            engine.each_captures(text, |captures| {
+                match Match::automata_captures(text, captures) {
+                    Ok(c) => {
+                        result.push(&c);
+                        true
+                    }
+                    Err(err) => {
+                        error = Some(err);
+                        false
+                    }
+                }
+            })?;
+            return match error {
+                Some(err) => Err(err),
+                None => Ok(result),
+            };
+        }
+        let names = self.regex.capture_names();
+        for captures in self.regex.captures_iter(text) {
+            let c = Match::captures(captures, names.clone())?;
+            result.push(&c);
+        }
+
+        Ok(result)
+    }
+
+    /// Replaces at most `limit` matches, and returns how many were replaced.
+    /// Counting the replacements of the regex crate is slower, so they are
+    /// only counted when profiling.
+    fn replace_counted(&self, text: &str, limit: usize, rep: &str) -> Result<(String, usize)> {
+        match &self.engine {
+            Some(engine) => engine.replacen(text, limit, rep),
+            None if self.profile.is_none() => {
+                Ok((self.regex.replacen(text, limit, rep).into_owned(), 0))
+            }
+            None => {
+                let mut counted = CountedReplacer { rep, count: 0 };
+                let replaced = self.regex.replacen(text, limit, counted.by_ref());
+                Ok((replaced.into_owned(), counted.count))
+            }
+        }
+    }
+
+    /// Returns the high level intermediate representation of the regular
+    /// expression.
+    pub fn hir(&self) -> Result<regex_syntax::hir::Hir> {
+        self.options
+            .parser()
+            .parse(self.regex.as_str())
+            .map_err(serde_wasm_bindgen::Error::new)
+    }
+
+    /// Returns a DFA that accepts the strings the regular expression matches,
+    /// somewhere or as a whole depending on `options`.
+    fn dfa(&self, options: &LanguageOptions) -> Result<dfa::Dfa> {
+        let hir = self.hir()?;
+        if options.whole {
+            dfa::whole_match(&hir, self.options.dfa_size_limit)
+        } else {
+            dfa::search_match(&hir, self.options.dfa_size_limit)
+        }
+    }
+
+    /// Compiles the high level intermediate representation of a pattern
+    /// translated from another syntax, see `from_translated`.
+    fn from_hir(hir: &regex_syntax::hir::Hir, options: JsValue) -> Result<RRegex> {
+        RRegex::from_translated(&hir.to_string(), options)
+    }
+
+    /// Compiles a pattern translated from another syntax, where only the
+    /// options that don't change the meaning of the pattern apply.
+    fn from_translated(pattern: &str, options: JsValue) -> Result<RRegex> {
+        let options: RegexOptions = types::options(options)?;
+        let options = RegexOptions {
+            case_insensitive: options.case_insensitive,
+            size_limit: options.size_limit,
+            dfa_size_limit: options.dfa_size_limit,
+            nest_limit: options.nest_limit,
+            engine: options.engine,
+            profile: options.profile,
+            ..RegexOptions::default()
+        };
+        RRegex::with_options(pattern, options)
+    }
+}
+
+/// Expands a replacement like the regex crate does, counting the matches
+/// replaced.
+struct CountedReplacer<'r> {
+    rep: &'r str,
+    count: usize,
+}
+
+impl Replacer for CountedReplacer<'_> {
+    fn replace_append(&mut self, caps: &regex::Captures<'_>, dst: &mut String) {
+        self.count += 1;
+        caps.expand(self.rep, dst);
+    }
+}
@@ -1,3 +1,16 @@
+use crate::cache;
+use crate::descriptor;
+use crate::descriptor::RegexSetDescriptor;
+use crate::dfa;
+use crate::dot;
+use crate::dot::DotOptions;
+use crate::language;
+use crate::literals;
+use crate::literals::LiteralsOptions;
+use crate::options::RegexOptions;
+use crate::profile;
+use crate::profile::Profile;
+use crate::types;
 use wasm_bindgen::prelude::*;
 
 type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
@@ -15,6 +28,8 @@ type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
 #[wasm_bindgen]
 pub struct RRegexSet {
     regexes: regex::RegexSet,
+    options: RegexOptions,
+    profile: Option<Profile>,
 }
 
 #[wasm_bindgen]
@@ -35,8 +50,15 @@ impl RRegexSet {
     /// const set = new RegexSet(["\\w+", "\\d+"])
     /// expect(set.is_match("foo")).toBe(true)
     /// ```
-    #[wasm_bindgen(constructor)]
-    pub fn new(list: &js_sys::Array) -> Result<RRegexSet> {
+    ///
+    /// The optional `options` are applied to every regular expression in the
+    /// set, see `RegexOptions`.
+    ///
+    /// @see https://docs.rs/regex/latest/regex/struct.RegexSetBuilder.html
+    /// @param {string[]} list - The regular expressions in the set
+    /// @param {RegexOptions} options - The options used to compile the regular expressions
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(list: &js_sys::Array, options: JsValue) -> Result<RRegexSet> {
         let mut patterns: Vec<String> = Vec::with_capacity(list.length() as usize);
         for (position, item) in list.iter().enumerate() {
           let pattern = item.as_string()
@@ -45,10 +67,8 @@ impl RRegexSet {
           patterns.push(pattern)
         };
 
-        let regexes = regex::RegexSet::new(patterns)
-          .map_err(|err| serde_wasm_bindgen::Error::new(err.to_string()))?;
-
-        Ok(RRegexSet { regexes })
+        let options: RegexOptions = types::options(options)?;
+        RRegexSet::with_options(patterns, options)
     }
 
     /// Returns true if and only if one of the regexes in this set matches
@@ -81,8 +101,11 @@ impl RRegexSet {
     /// @param {string} text - The string against which to match the regular expression
     /// @return {boolean}
     #[wasm_bindgen(skip_jsdoc, js_name = isMatch)]
-    pub fn is_match(&self, text: &str) -> bool {
-        self.regexes.is_match(text)
+    pub fn is_match(&self, text: &str) -> Result<bool> {
+        self.measure("isMatch", text, || {
+            let matched = self.regexes.is_match(text);
+            Ok((matched, usize::from(matched)))
+        })
     }
 
     /// Returns the set of regular expressions that match in the given text.
@@ -122,7 +145,231 @@ impl RRegexSet {
     /// @param {string} text - The string against which to match the regular expression
     /// @return {number[]}
     #[wasm_bindgen(skip_jsdoc)]
-    pub fn matches(&self, text: &str) -> Vec<JsValue> {
-        self.regexes.matches(text).iter().map(JsValue::from).collect()
+    pub fn matches(&self, text: &str) -> Result<Vec<JsValue>> {
+        self.measure("matches", text, || {
+            let matches: Vec<JsValue> = self
+                .regexes
+                .matches(text)
+                .iter()
+                .map(JsValue::from)
+                .collect();
+            let count = matches.len();
+            Ok((matches, count))
+        })
+    }
+
+    /// Returns the sequence of literals of each regular expression in the
+    /// set, in the same order they were given to the constructor.
+    ///
+    /// See `RRegex.literals` for details about the options and the returned
+    /// sequences.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegexSet } from "rregex"
+    ///
+    /// const set = new RRegexSet(["foo\\d+", "bar$"])
+    /// const [foo, bar] = set.literals({ kind: "suffix" })
+    /// expect(foo.finite).toBe(false)
+    /// expect(bar.literals.map(lit => lit.value)).toEqual(["bar"])
+    /// ```
+    ///
+    /// @see https://docs.rs/regex-syntax/latest/regex_syntax/hir/literal/struct.Extractor.html
+    /// @param {LiteralsOptions} options - The kind of literals and the limits used to extract them
+    /// @returns {LiteralSeq[]}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn literals(&self, options: JsValue) -> Result<Vec<JsValue>> {
+        let options: LiteralsOptions = types::options(options)?;
+        self.regexes
+            .patterns()
+            .iter()
+            .map(|pattern| literals::extract(&mut self.options.parser(), pattern, &options))
+            .collect()
+    }
+
+    /// Finds the patterns that can never win because an earlier pattern
+    /// matches every text they match (they are shadowed), and the earlier
+    /// patterns that match some of the same texts (they overlap), useful to
+    /// audit an ordered table of routes or rules.
+    ///
+    /// Like `isMatch` and `matches`, patterns are compared by the texts they
+    /// match somewhere, so `foo` is shadowed by `o`. Every pattern is
+    /// compiled into a DFA and compared with each earlier pattern with
+    /// product constructions, so the analysis is exact, and every relation
+    /// comes with the shortest text that proves it. Unicode word boundaries
+    /// are not supported, use `(?-u:\b)` instead.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegexSet } from "rregex"
+    ///
+    /// const set = new RRegexSet(["^/users/[0-9]+$", "^/users/", "^/users/me$", "^/[a-z]+/1"])
+    /// const [, users, me, slug] = set.analyze()
+    /// expect(users.overlapsWith).toEqual([{ index: 0, example: "/users/0" }])
+    /// expect(me.shadowedBy).toEqual([{ index: 1, example: "/users/me" }])
+    /// expect(slug.overlapsWith.map((witness) => witness.index)).toEqual([0, 1])
+    /// ```
+    ///
+    /// @returns {PatternAnalysis[]}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn analyze(&self) -> Result<JsValue> {
+        let dfas = self
+            .patterns()
+            .iter()
+            .map(|pattern| {
+                let hir = self
+                    .options
+                    .parser()
+                    .parse(pattern)
+                    .map_err(serde_wasm_bindgen::Error::new)?;
+                dfa::search_match(&hir, self.options.dfa_size_limit)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let analyses = language::analyze(self.patterns(), &dfas);
+        serde_wasm_bindgen::to_value(&analyses)
+    }
+
+    /// Renders the automaton the set is compiled into as a Graphviz DOT
+    /// digraph, where match states list the indices of the patterns they
+    /// match. See `RRegex.toDot` for how the automata are drawn.
+    ///
+    /// Unlike `RRegex`, the DFA of a set reports every pattern that
+    /// matches, so its match states may match several patterns at once.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegexSet } from "rregex"
+    ///
+    /// const dot = new RRegexSet(["ab", "a[a-z]"]).toDot({ automaton: "dfa" })
+    /// expect(dot).toContain("match 0, 1")
+    /// ```
+    ///
+    /// @param {DotOptions} options - The automaton to render
+    /// @returns {string}
+    #[wasm_bindgen(skip_jsdoc, js_name = toDot)]
+    pub fn to_dot(&self, options: JsValue) -> Result<String> {
+        let options: DotOptions = types::options(options)?;
+        let hirs = self
+            .patterns()
+            .iter()
+            .map(|pattern| {
+                self.options
+                    .parser()
+                    .parse(pattern)
+                    .map_err(serde_wasm_bindgen::Error::new)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        dot::to_dot(&hirs, &options, &self.options)
+    }
+
+    /// Compiles a regex set like the constructor, reusing the compiled set
+    /// when the same patterns were already compiled with the same options,
+    /// see `RRegexCache`.
+    ///
+    /// @param {string[]} list - The regular expressions in the set
+    /// @param {RegexOptions} options - The options used to compile the regular expressions
+    /// @returns {RRegexSet}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn cached(list: Vec<String>, options: JsValue) -> Result<RRegexSet> {
+        let options: RegexOptions = types::options(options)?;
+        let regexes = cache::regex_set(&list, &options)?;
+        Ok(RRegexSet::from_regexes(regexes, options))
+    }
+
+    /// Returns a plain object with the patterns and the options of the set,
+    /// which can be sent to a worker with `postMessage` and compiled again
+    /// there with `RRegexSet.fromDescriptor`.
+    ///
+    /// # Example
+    ///
+    /// ```typescript
+    /// import { RRegexSet } from "rregex"
+    ///
+    /// const descriptor = new RRegexSet(["\\d+", "[a-z]+"]).toDescriptor()
+    /// const set = RRegexSet.fromDescriptor(structuredClone(descriptor))
+    /// expect(set.matches("abc")).toEqual([1])
+    /// ```
+    ///
+    /// @returns {RegexSetDescriptor}
+    #[wasm_bindgen(skip_jsdoc, js_name = toDescriptor)]
+    pub fn to_descriptor(&self) -> Result<JsValue> {
+        let descriptor = RegexSetDescriptor {
+            patterns: self.patterns().to_vec(),
+            options: self.options.clone(),
+        };
+        descriptor::to_value(&descriptor, None)
+    }
+
+    /// Compiles a regex set described by `toDescriptor`.
+    ///
+    /// @param {RegexSetDescriptor} descriptor - The descriptor returned by `toDescriptor`
+    /// @returns {RRegexSet}
+    #[wasm_bindgen(skip_jsdoc, js_name = fromDescriptor)]
+    pub fn from_descriptor(descriptor: JsValue) -> Result<RRegexSet> {
+        let RegexSetDescriptor { patterns, options } = serde_wasm_bindgen::from_value(descriptor)?;
+        RRegexSet::with_options(patterns, options)
+    }
+
+    /// Returns the counters recorded by `isMatch` and `matches` when
+    /// compiled with `profile: true`, see `RRegex.stats`. The matches of
+    /// `matches` count the patterns that matched.
+    ///
+    /// @returns {RegexStats}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn stats(&self) -> Result<JsValue> {
+        profile::stats(self.profile.as_ref())
+    }
+
+    /// Clears the counters returned by `stats`.
+    #[wasm_bindgen(js_name = resetStats)]
+    pub fn reset_stats(&self) {
+        if let Some(profile) = &self.profile {
+            profile.reset();
+        }
+    }
+}
+
+impl RRegexSet {
+    pub fn with_options(patterns: Vec<String>, options: RegexOptions) -> Result<RRegexSet> {
+        let regexes = options
+            .regex_set_builder(patterns)
+            .build()
+            .map_err(|err| serde_wasm_bindgen::Error::new(err.to_string()))?;
+        Ok(RRegexSet::from_regexes(regexes, options))
+    }
+
+    /// Wraps a set compiled with `options`.
+    fn from_regexes(regexes: regex::RegexSet, options: RegexOptions) -> RRegexSet {
+        let profile = if options.profile {
+            Some(Profile::default())
+        } else {
+            None
+        };
+
+        RRegexSet {
+            regexes,
+            options,
+            profile,
+        }
+    }
+
+    /// Runs a search method, recording it when profiling. The regex crate
+    /// doesn't expose its lazy DFA caches, so their clears aren't counted.
+    fn measure<T, F>(&self, method: &'static str, text: &str, search: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<(T, usize)>,
+    {
+        profile::measure(self.profile.as_ref(), method, text.len(), || 0, search)
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        self.regexes.patterns()
+    }
+
+    pub fn set_matches(&self, text: &str) -> regex::SetMatches {
+        self.regexes.matches(text)
     }
 }
@@ -0,0 +1,180 @@
+use regex_automata::util::captures::Captures;
+use regex_automata::{Anchored, Input, Span};
+use wasm_bindgen::prelude::*;
+
+use crate::rregex::RRegex;
+use crate::types::Match;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+/// Scans a string with a cursor, like Ruby's `StringScanner`, for hand
+/// written parsers: `scan` matches a regular expression at the cursor and
+/// moves the cursor past the match, so every call only looks at the rest
+/// of the string.
+///
+/// The string is copied once into the scanner, and every search starts at
+/// the cursor without slicing or copying it again, so look-behind
+/// assertions like `\b` see the text before the cursor. Positions are byte
+/// offsets in the UTF-8 encoded string, like the ones of `RRegex`.
+///
+/// # Example
+///
+/// ```typescript
+/// import { RRegex, RScanner } from "rregex"
+///
+/// const word = new RRegex("\\w+")
+/// const space = new RRegex("\\s+")
+/// const scanner = new RScanner("let x = 42")
+/// expect(scanner.scan(word)).toBe("let")
+/// expect(scanner.scan(word)).toBeUndefined()
+/// expect(scanner.skip(space)).toBe(1)
+/// expect(scanner.check(word)).toBe("x")
+/// expect(scanner.pos).toBe(4)
+/// expect(scanner.scanUntil(new RRegex("(?P<value>[0-9]+)"))).toBe("x = 42")
+/// expect(scanner.captures().name.value.value).toBe("42")
+/// expect(scanner.eos).toBe(true)
+/// ```
+///
+/// @see https://docs.ruby-lang.org/en/master/StringScanner.html
+#[wasm_bindgen]
+pub struct RScanner {
+    text: String,
+    pos: usize,
+    last: Option<Captures>,
+}
+
+#[wasm_bindgen]
+impl RScanner {
+    /// Creates a scanner of `text`, with the cursor at its start.
+    ///
+    /// @param {string} text - The string to scan
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(text: String) -> RScanner {
+        RScanner {
+            text,
+            pos: 0,
+            last: None,
+        }
+    }
+
+    /// Matches `re` at the cursor and, when it matches, moves the cursor to
+    /// the end of the match and returns it, or returns `undefined`.
+    ///
+    /// @param {RRegex} re - The regular expression to match at the cursor
+    /// @returns {string | undefined}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn scan(&mut self, re: &RRegex) -> Result<Option<String>> {
+        let start = self.pos;
+        Ok(self
+            .advance(re, Anchored::Yes)?
+            .map(|m| self.text[start..m.end].to_owned()))
+    }
+
+    /// Like `scan`, but returns the match without moving the cursor.
+    ///
+    /// @param {RRegex} re - The regular expression to match at the cursor
+    /// @returns {string | undefined}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn check(&mut self, re: &RRegex) -> Result<Option<String>> {
+        Ok(self
+            .search(re, Anchored::Yes)?
+            .map(|m| self.text[m.range()].to_owned()))
+    }
+
+    /// Like `scan`, but returns the length of the match, in bytes, instead
+    /// of the match itself.
+    ///
+    /// @param {RRegex} re - The regular expression to match at the cursor
+    /// @returns {number | undefined}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn skip(&mut self, re: &RRegex) -> Result<Option<usize>> {
+        Ok(self.advance(re, Anchored::Yes)?.map(|m| m.len()))
+    }
+
+    /// Searches `re` anywhere after the cursor and, when it matches, moves
+    /// the cursor to the end of the match and returns the string from the
+    /// previous cursor to the end of the match, or returns `undefined`.
+    ///
+    /// @param {RRegex} re - The regular expression to search after the cursor
+    /// @returns {string | undefined}
+    #[wasm_bindgen(skip_jsdoc, js_name = scanUntil)]
+    pub fn scan_until(&mut self, re: &RRegex) -> Result<Option<String>> {
+        let start = self.pos;
+        Ok(self
+            .advance(re, Anchored::No)?
+            .map(|m| self.text[start..m.end].to_owned()))
+    }
+
+    /// Returns the next `n` characters (code points) after the cursor,
+    /// or fewer at the end of the string, without moving the cursor.
+    ///
+    /// @param {number} n - The number of characters
+    /// @returns {string}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn peek(&self, n: usize) -> String {
+        self.text[self.pos..].chars().take(n).collect()
+    }
+
+    /// Returns the capture groups of the last match of `scan`, `check`,
+    /// `skip` or `scanUntil`, in the shape returned by `RRegex.captures`,
+    /// or `undefined` when the last one didn't match.
+    ///
+    /// @returns {Captures | undefined}
+    #[wasm_bindgen(skip_jsdoc)]
+    pub fn captures(&self) -> Result<JsValue> {
+        match &self.last {
+            Some(captures) => Match::automata_captures(&self.text, captures),
+            None => Ok(JsValue::UNDEFINED),
+        }
+    }
+
+    /// The position of the cursor, as a byte offset. Setting it moves the
+    /// cursor, e.g. to backtrack, to the given offset, clamped to the end of
+    /// the string and moved back to the start of the character it falls in.
+    #[wasm_bindgen(getter)]
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_pos(&mut self, pos: usize) {
+        let mut pos = pos.min(self.text.len());
+        while !self.text.is_char_boundary(pos) {
+            pos -= 1;
+        }
+        self.pos = pos;
+    }
+
+    /// Whether the cursor is at the end of the string.
+    #[wasm_bindgen(getter)]
+    pub fn eos(&self) -> bool {
+        self.pos == self.text.len()
+    }
+
+    /// The rest of the string, after the cursor.
+    #[wasm_bindgen(getter)]
+    pub fn rest(&self) -> String {
+        self.text[self.pos..].to_owned()
+    }
+}
+
+impl RScanner {
+    /// Searches `re` from the cursor, remembering the capture groups of the
+    /// match for `captures`.
+    fn search(&mut self, re: &RRegex, anchored: Anchored) -> Result<Option<Span>> {
+        let input = Input::new(&self.text).range(self.pos..).anchored(anchored);
+        let captures = re.search_captures(&input)?;
+        let span = captures.get_match().map(|m| m.span());
+        self.last = Some(captures).filter(Captures::is_match);
+        Ok(span)
+    }
+
+    /// Like `search`, moving the cursor to the end of the match.
+    fn advance(&mut self, re: &RRegex, anchored: Anchored) -> Result<Option<Span>> {
+        let span = self.search(re, anchored)?;
+        if let Some(span) = span {
+            self.pos = span.end;
+        }
+        Ok(span)
+    }
+}
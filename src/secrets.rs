@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+/// A built-in rule: its id, description, pattern and minimum entropy.
+pub struct Rule {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub pattern: &'static str,
+    pub min_entropy: Option<f64>,
+}
+
+/// The rules every `RSecretScanner` starts with, unless disabled. The
+/// secret is captured in the group `secret`, and rules with a recognizable
+/// prefix come first, so they win over the generic ones.
+pub const BUILTIN: &[Rule] = &[
+    Rule {
+        id: "aws-access-key-id",
+        description: "AWS access key ID",
+        pattern: r"\b(?P<secret>(?:AKIA|ASIA|ABIA|ACCA)[A-Z2-7]{16})\b",
+        min_entropy: Some(3.0),
+    },
+    Rule {
+        id: "aws-secret-access-key",
+        description: "AWS secret access key",
+        pattern: r#"(?i:aws)[\w.-]{0,20}(?i:secret)[\w.-]{0,20}["']?\s*[:=]\s*["']?(?P<secret>[A-Za-z0-9/+]{40})(?:[^A-Za-z0-9/+=]|\z)"#,
+        min_entropy: Some(4.0),
+    },
+    Rule {
+        id: "gcp-api-key",
+        description: "Google Cloud API key",
+        pattern: r"\b(?P<secret>AIza[0-9A-Za-z_-]{35})(?:[^0-9A-Za-z_-]|\z)",
+        min_entropy: Some(3.5),
+    },
+    Rule {
+        id: "github-token",
+        description: "GitHub token",
+        pattern: r"\b(?P<secret>gh[pousr]_[A-Za-z0-9]{36,255})\b",
+        min_entropy: Some(3.5),
+    },
+    Rule {
+        id: "slack-token",
+        description: "Slack token",
+        pattern: r"\b(?P<secret>xox[abposr]-[A-Za-z0-9-]{10,})",
+        min_entropy: Some(3.0),
+    },
+    Rule {
+        id: "stripe-secret-key",
+        description: "Stripe secret or restricted key",
+        pattern: r"\b(?P<secret>[rs]k_live_[A-Za-z0-9]{24,})\b",
+        min_entropy: Some(3.5),
+    },
+    Rule {
+        id: "jwt",
+        description: "JSON Web Token",
+        pattern: r"\b(?P<secret>eyJ[A-Za-z0-9_-]{10,}\.eyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,})",
+        min_entropy: Some(3.5),
+    },
+    Rule {
+        id: "private-key",
+        description: "Private key",
+        pattern: r"(?s)-----BEGIN[A-Z0-9 ]* PRIVATE KEY(?: BLOCK)?-----(?P<secret>.*?)(?:-----END[A-Z0-9 ]* PRIVATE KEY(?: BLOCK)?-----|\z)",
+        min_entropy: None,
+    },
+    Rule {
+        id: "generic-secret",
+        description: "Secret assigned to a key like password, token or api_key",
+        pattern: r#"(?i)[\w.-]{0,30}(?:api_?key|secret|token|passw(?:or)?d|pwd|credential|auth)[\w.-]{0,30}["']?\s*[:=]\s*["']?(?P<secret>[\w+/=.~-]{16,})"#,
+        min_entropy: Some(3.5),
+    },
+    Rule {
+        id: "generic-token",
+        description: "High-entropy token without a key, like a random API key",
+        pattern: r"\b(?P<secret>[A-Za-z0-9][A-Za-z0-9+/_-]{31,}={0,2})",
+        min_entropy: Some(4.5),
+    },
+];
+
+/// Returns the Shannon entropy of `text`, in bits per character, which is
+/// close to `0` for repetitive text like `aaaa` or `password`, and high for
+/// random tokens: about `4` for hexadecimal digits and `6` for base64.
+pub fn entropy(text: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    let mut total = 0;
+    for c in text.chars() {
+        *counts.entry(c).or_default() += 1;
+        total += 1;
+    }
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Returns `text` with `secret`, a range of it, replaced by its first
+/// characters followed by `*****`. Only a quarter of the secret, and at
+/// most 4 characters, are kept, so short secrets are hidden entirely.
+pub fn redact(text: &str, secret: std::ops::Range<usize>) -> String {
+    let value = &text[secret.clone()];
+    let kept = (value.chars().count() / 4).min(4);
+    let kept = value
+        .char_indices()
+        .nth(kept)
+        .map_or(value.len(), |(i, _)| i);
+
+    let mut redacted = String::with_capacity(text.len());
+    redacted.push_str(&text[..secret.start]);
+    redacted.push_str(&value[..kept]);
+    redacted.push_str("*****");
+    redacted.push_str(&text[secret.end..]);
+    redacted
+}
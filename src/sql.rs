@@ -0,0 +1,74 @@
+use regex_syntax::hir::{Dot, Hir, Look, Repetition};
+
+use crate::error::PatternError;
+use crate::posix;
+
+/// Returns the escape character of a `LIKE` or `SIMILAR TO` pattern, an
+/// empty string disables escaping.
+pub fn escape_char(escape: Option<&str>) -> Result<Option<char>, PatternError> {
+    let escape = match escape {
+        None | Some("") => return Ok(None),
+        Some(escape) => escape,
+    };
+    let mut chars = escape.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(Some(c)),
+        _ => Err(PatternError::syntax(
+            "the escape character must be a single character",
+            escape,
+            0,
+            escape.len(),
+        )),
+    }
+}
+
+/// Translates a SQL `LIKE` pattern into a high level intermediate
+/// representation that matches the whole string.
+pub fn like(pattern: &str, escape: Option<char>) -> Result<Hir, PatternError> {
+    let mut items = vec![Hir::look(Look::Start)];
+    let mut chars = pattern.char_indices();
+    while let Some((offset, c)) = chars.next() {
+        let item = match c {
+            c if Some(c) == escape => match chars.next() {
+                Some((_, c)) if c == '%' || c == '_' || Some(c) == escape => literal(c),
+                Some((end, c)) => {
+                    return Err(PatternError::syntax(
+                        "the escape character must be followed by '%', '_' or itself",
+                        pattern,
+                        offset,
+                        end + c.len_utf8(),
+                    ));
+                }
+                None => {
+                    return Err(PatternError::syntax(
+                        "trailing escape character",
+                        pattern,
+                        offset,
+                        pattern.len(),
+                    ));
+                }
+            },
+            '%' => Hir::repetition(Repetition {
+                min: 0,
+                max: None,
+                greedy: true,
+                sub: Box::new(Hir::dot(Dot::AnyChar)),
+            }),
+            '_' => Hir::dot(Dot::AnyChar),
+            c => literal(c),
+        };
+        items.push(item);
+    }
+    items.push(Hir::look(Look::End));
+    Ok(Hir::concat(items))
+}
+
+/// Translates a SQL `SIMILAR TO` pattern into a high level intermediate
+/// representation that matches the whole string.
+pub fn similar(pattern: &str, escape: Option<char>) -> Result<Hir, PatternError> {
+    posix::parse(pattern, posix::Flavor::Similar(escape))
+}
+
+fn literal(c: char) -> Hir {
+    Hir::literal(c.encode_utf8(&mut [0; 4]).as_bytes())
+}
@@ -0,0 +1,208 @@
+use regex_syntax::ast::{self, Ast};
+use std::collections::HashSet;
+use wasm_bindgen::prelude::*;
+
+use crate::descriptor::RegexDescriptor;
+use crate::options::RegexOptions;
+
+type Result<T> = std::result::Result<T, serde_wasm_bindgen::Error>;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TEMPLATE_VALUE_TYPE: &'static str = r#"/**
+ * A value interpolated in `RRegex.fromTemplate`: strings and numbers are
+ * matched literally, in a non-capturing group so a quantifier after them
+ * repeats the whole value, arrays match any of their items literally, and
+ * regular expressions are embedded in a non-capturing group, with their
+ * capture groups renamed when an earlier one has the same name.
+ */
+export type TemplateValue = string | number | (string | number)[] | RRegex"#;
+
+/// Joins the pattern fragments of a template with its interpolated values.
+///
+/// The raw fragments are used when `strings` is a template strings array,
+/// so `\d` doesn't need to be written `\\d` in a tagged template.
+pub fn pattern(strings: &JsValue, values: &[JsValue]) -> Result<String> {
+    let raw = js_sys::Reflect::get(strings, &JsValue::from("raw"))?;
+    let strings: Vec<String> = if raw.is_undefined() {
+        serde_wasm_bindgen::from_value(strings.clone())?
+    } else {
+        serde_wasm_bindgen::from_value(raw)?
+    };
+    if strings.len() != values.len() + 1 {
+        return Err(serde_wasm_bindgen::Error::new(format!(
+            "expected {} values between {} strings, found {}",
+            strings.len().saturating_sub(1),
+            strings.len(),
+            values.len()
+        )));
+    }
+
+    let mut pattern = strings[0].clone();
+    let mut names = HashSet::new();
+    for (position, (value, string)) in values.iter().zip(&strings[1..]).enumerate() {
+        pattern.push_str(&interpolate(value, position, &mut names)?);
+        pattern.push_str(string);
+    }
+    Ok(pattern)
+}
+
+/// Writes an interpolated value as a pattern: strings and numbers are
+/// escaped in a group, arrays become an alternation of their escaped items, and
+/// regular expressions (anything with a `toDescriptor` method returning
+/// a `RegexDescriptor`) are embedded in a group. `names` are the names of
+/// the capture groups of the regular expressions embedded so far.
+fn interpolate(value: &JsValue, position: usize, names: &mut HashSet<String>) -> Result<String> {
+    if let Some(literal) = literal(value) {
+        return Ok(format!("(?:{})", regex::escape(&literal)));
+    }
+    if js_sys::Array::is_array(value) {
+        let items = js_sys::Array::from(value);
+        if items.length() == 0 {
+            // an alternation without alternatives never matches
+            return Ok(String::from("[a&&b]"));
+        }
+        let mut alternatives = Vec::with_capacity(items.length() as usize);
+        for item in items.iter() {
+            let item = literal(&item).ok_or_else(|| {
+                serde_wasm_bindgen::Error::new(format!(
+                    "value in position {} has an item that is not a string or a number",
+                    position
+                ))
+            })?;
+            alternatives.push(regex::escape(&item));
+        }
+        return Ok(format!("(?:{})", alternatives.join("|")));
+    }
+
+    let to_descriptor = js_sys::Reflect::get(value, &JsValue::from("toDescriptor"))
+        .ok()
+        .and_then(|method| method.dyn_into::<js_sys::Function>().ok());
+    match to_descriptor {
+        Some(to_descriptor) => {
+            let descriptor = to_descriptor.call0(value)?;
+            let RegexDescriptor { pattern, options } = serde_wasm_bindgen::from_value(descriptor)?;
+            let pattern = rename_groups(&pattern, &options, names)?;
+            Ok(embed(&pattern, &options))
+        }
+        None => Err(serde_wasm_bindgen::Error::new(format!(
+            "value in position {} is not a string, a number, an array or a RRegex",
+            position
+        ))),
+    }
+}
+
+/// Returns a string, or a number written like `String(number)` does, e.g.
+/// `1e21`.
+fn literal(value: &JsValue) -> Option<String> {
+    match value.as_string() {
+        Some(string) => Some(string),
+        None => value
+            .as_f64()
+            .and_then(|number| js_sys::Number::from(number).to_string(10).ok())
+            .map(String::from),
+    }
+}
+
+/// Renames the capture groups of `pattern` whose name is already in
+/// `names`, with the first free suffix among `_2`, `_3`..., e.g. when the
+/// same `RRegex` is embedded twice, and adds the names it ends up with to
+/// `names`.
+fn rename_groups(
+    pattern: &str,
+    options: &RegexOptions,
+    names: &mut HashSet<String>,
+) -> Result<String> {
+    let ast = ast::parse::ParserBuilder::new()
+        .ignore_whitespace(options.ignore_whitespace)
+        .octal(options.octal)
+        .build()
+        .parse(pattern)
+        .map_err(serde_wasm_bindgen::Error::new)?;
+    let groups = ast::visit(&ast, CaptureNames(Vec::new())).unwrap_or_default();
+    let own: HashSet<&str> = groups.iter().map(|(_, name)| name.as_str()).collect();
+
+    let mut renamed = String::with_capacity(pattern.len());
+    let mut end = 0;
+    for (span, name) in &groups {
+        let mut unique = name.clone();
+        let mut suffix = 2;
+        while names.contains(&unique) || (unique != *name && own.contains(unique.as_str())) {
+            unique = format!("{}_{}", name, suffix);
+            suffix += 1;
+        }
+        renamed.push_str(&pattern[end..span.start.offset]);
+        renamed.push_str(&unique);
+        end = span.end.offset;
+        names.insert(unique);
+    }
+    renamed.push_str(&pattern[end..]);
+    Ok(renamed)
+}
+
+/// Collects the names of the capture groups of a pattern, with their spans,
+/// in the order they appear.
+struct CaptureNames(Vec<(ast::Span, String)>);
+
+impl ast::Visitor for CaptureNames {
+    type Output = Vec<(ast::Span, String)>;
+    type Err = ();
+
+    fn finish(self) -> std::result::Result<Self::Output, ()> {
+        Ok(self.0)
+    }
+
+    fn visit_pre(&mut self, ast: &Ast) -> std::result::Result<(), ()> {
+        if let Ast::Group(group) = ast {
+            if let ast::GroupKind::CaptureName { name, .. } = &group.kind {
+                self.0.push((name.span, name.name.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Wraps `pattern` in a non-capturing group, with the flags of the options
+/// it was compiled with that differ from the defaults. Capture groups are
+/// numbered by position, so the groups of `pattern` are renumbered after
+/// the ones before it.
+pub fn embed(pattern: &str, options: &RegexOptions) -> String {
+    let defaults = RegexOptions::default();
+    let flags = [
+        ('i', options.case_insensitive, defaults.case_insensitive),
+        ('m', options.multi_line, defaults.multi_line),
+        (
+            's',
+            options.dot_matches_new_line,
+            defaults.dot_matches_new_line,
+        ),
+        ('R', options.crlf, defaults.crlf),
+        ('U', options.swap_greed, defaults.swap_greed),
+        ('u', options.unicode, defaults.unicode),
+        ('x', options.ignore_whitespace, defaults.ignore_whitespace),
+    ];
+    let enabled: String = flags
+        .iter()
+        .filter(|(_, value, default)| *value && !*default)
+        .map(|(flag, _, _)| *flag)
+        .collect();
+    let disabled: String = flags
+        .iter()
+        .filter(|(_, value, default)| !*value && *default)
+        .map(|(flag, _, _)| *flag)
+        .collect();
+
+    let mut group = String::from("(?");
+    group.push_str(&enabled);
+    if !disabled.is_empty() {
+        group.push('-');
+        group.push_str(&disabled);
+    }
+    group.push(':');
+    group.push_str(pattern);
+    if options.ignore_whitespace {
+        // a trailing comment would hide the end of the group
+        group.push('\n');
+    }
+    group.push(')');
+    group
+}
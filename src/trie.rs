@@ -0,0 +1,166 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const LITERAL_LIST_OPTIONS_TYPE: &'static str = r#"/**
+ * Options used by `RRegex.fromLiterals` to compile a list of words. Besides
+ * `caseInsensitive`, only the limits, `engine` and `profile` of
+ * `RegexOptions` are used, and past the levels of prefixes `nestLimit`
+ * allows, the remaining words are written as an alternation.
+ */
+export type LiteralListOptions = RegexOptions & {
+  /**
+   * Only matches whole words: a word can't be preceded or followed by a
+   * word character, so `cat` doesn't match in `concatenate`. Unlike `\b`,
+   * words starting or ending with other characters, like `c++`, match too.
+   * Defaults to `false`.
+   */
+  wordBoundary?: boolean
+
+  /** Only matches when the whole string is one of the words. Defaults to `false`. */
+  anchored?: boolean
+}"#;
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LiteralListOptions {
+    pub word_boundary: bool,
+    pub anchored: bool,
+    pub nest_limit: Option<u32>,
+}
+
+/// The nest limit of the regex crate when none is set.
+const DEFAULT_NEST_LIMIT: u32 = 250;
+
+/// How many levels of the nest limit a level of the prefix tree takes at
+/// most: the `?` of an optional suffix, its group, the alternation and the
+/// concatenation of a branch.
+const LEVEL_NESTING: u32 = 4;
+
+/// A prefix tree of words, by character.
+#[derive(Default)]
+struct Node {
+    end: bool,
+    children: BTreeMap<char, Node>,
+}
+
+impl Node {
+    fn insert(&mut self, word: &str) {
+        let mut node = self;
+        for c in word.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        node.end = true;
+    }
+
+    /// Writes a pattern matching the suffixes below this node, where the
+    /// common prefixes are factored out and the longest suffix is
+    /// preferred, e.g. `foo(?:bar)?` for `foo` and `foobar`. Past
+    /// `max_depth` levels, the suffixes are written as an alternation
+    /// instead, so long chains of words that are prefixes of each other
+    /// stay within the nest limit.
+    fn write(&self, pattern: &mut String, depth: usize, max_depth: usize) {
+        if self.children.is_empty() {
+            return;
+        }
+        let flat = depth >= max_depth;
+        let group = self.end || self.children.len() > 1 || flat;
+        if group {
+            pattern.push_str("(?:");
+        }
+        if flat {
+            let mut suffixes = Vec::new();
+            self.suffixes(&mut String::new(), &mut suffixes);
+            for (i, suffix) in suffixes.iter().enumerate() {
+                if i > 0 {
+                    pattern.push('|');
+                }
+                pattern.push_str(&regex::escape(suffix));
+            }
+        } else {
+            for (i, (&c, child)) in self.children.iter().enumerate() {
+                if i > 0 {
+                    pattern.push('|');
+                }
+                // runs of nodes with a single child are a single literal
+                let mut literal = String::new();
+                literal.push(c);
+                let mut node = child;
+                while !node.end && node.children.len() == 1 {
+                    let (&c, child) = node.children.iter().next().unwrap();
+                    literal.push(c);
+                    node = child;
+                }
+                pattern.push_str(&regex::escape(&literal));
+                node.write(pattern, depth + 1, max_depth);
+            }
+        }
+        if group {
+            pattern.push(')');
+        }
+        if self.end {
+            pattern.push('?');
+        }
+    }
+
+    /// Collects the suffixes below this node after `prefix`, in the order
+    /// `write` prefers them.
+    fn suffixes(&self, prefix: &mut String, suffixes: &mut Vec<String>) {
+        for (&c, child) in &self.children {
+            prefix.push(c);
+            child.suffixes(prefix, suffixes);
+            if child.end {
+                suffixes.push(prefix.clone());
+            }
+            prefix.pop();
+        }
+    }
+}
+
+impl Drop for Node {
+    /// Drops the nodes below this one without recursing, as long words make
+    /// deep trees.
+    fn drop(&mut self) {
+        let mut nodes: Vec<Node> = std::mem::take(&mut self.children).into_values().collect();
+        while let Some(mut node) = nodes.pop() {
+            nodes.extend(std::mem::take(&mut node.children).into_values());
+        }
+    }
+}
+
+/// Returns a pattern matching any of `words`, written from a prefix tree of
+/// them, which is much smaller than the alternation of every word. Empty
+/// words are ignored, and an empty list never matches.
+pub fn pattern<S: AsRef<str>>(words: &[S], options: &LiteralListOptions) -> String {
+    let mut root = Node::default();
+    for word in words.iter().map(AsRef::as_ref) {
+        if !word.is_empty() {
+            root.insert(word);
+        }
+    }
+
+    let mut pattern = String::new();
+    if options.anchored {
+        pattern.push_str("\\A");
+    }
+    if options.word_boundary {
+        pattern.push_str("\\b{start-half}");
+    }
+    if root.children.is_empty() {
+        pattern.push_str("[a&&b]");
+    } else {
+        // the assertions around the words take one more level, and the
+        // alternation of the deepest suffixes takes a level of the tree
+        let limit = options.nest_limit.unwrap_or(DEFAULT_NEST_LIMIT);
+        let max_depth = (limit.saturating_sub(1) / LEVEL_NESTING).saturating_sub(1);
+        root.write(&mut pattern, 0, max_depth as usize);
+    }
+    if options.word_boundary {
+        pattern.push_str("\\b{end-half}");
+    }
+    if options.anchored {
+        pattern.push_str("\\z");
+    }
+    pattern
+}
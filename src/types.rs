@@ -1,9 +1,35 @@
 use regex_syntax::hir;
+use serde::de::DeserializeOwned;
 use serde::ser::SerializeStruct;
 use serde::Serialize;
 use std::convert::TryFrom;
 use wasm_bindgen::prelude::*;
 
+/// Deserializes an options object received from javascript, `undefined` and
+/// `null` are accepted and resolve to the default options.
+pub fn options<T>(value: JsValue) -> Result<T, serde_wasm_bindgen::Error>
+where
+    T: DeserializeOwned + Default,
+{
+    if value.is_undefined() || value.is_null() {
+        Ok(T::default())
+    } else {
+        serde_wasm_bindgen::from_value(value)
+    }
+}
+
+/// Serializes a byte slice as an `Uint8Array`.
+pub struct Bytes<'b>(pub &'b [u8]);
+
+impl<'b> Serialize for Bytes<'b> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
 #[wasm_bindgen(typescript_custom_section)]
 const MATCH_TYPE: &'static str = r#"/**
  * Represents a single match of a regex in a haystack.
@@ -58,11 +84,30 @@ export type Match = {
   value: string
 }"#;
 
-pub struct Match<'t>(regex::Match<'t>);
+pub struct Match<'t> {
+    start: usize,
+    end: usize,
+    value: &'t str,
+}
+
+impl<'t> Match<'t> {
+    /// A match of `text[start..end]`.
+    pub fn new(text: &'t str, start: usize, end: usize) -> Self {
+        Match {
+            start,
+            end,
+            value: &text[start..end],
+        }
+    }
+}
 
 impl<'t> From<regex::Match<'t>> for Match<'t> {
     fn from(value: regex::Match<'t>) -> Self {
-        Match(value)
+        Match {
+            start: value.start(),
+            end: value.end(),
+            value: value.as_str(),
+        }
     }
 }
 
@@ -79,9 +124,9 @@ impl<'t> Serialize for Match<'t> {
         S: serde::Serializer,
     {
         let mut hir = serializer.serialize_struct("Match", 3)?;
-        hir.serialize_field("start", &self.0.start())?;
-        hir.serialize_field("end", &self.0.end())?;
-        hir.serialize_field("value", &self.0.as_str())?;
+        hir.serialize_field("start", &self.start)?;
+        hir.serialize_field("end", &self.end)?;
+        hir.serialize_field("value", &self.value)?;
         hir.end()
     }
 }
@@ -140,17 +185,41 @@ impl<'t> Match<'t> {
         captures: regex::Captures,
         captures_names: regex::CaptureNames,
     ) -> Result<JsValue, serde_wasm_bindgen::Error> {
+        let groups = captures_names
+            .enumerate()
+            .map(|(index, name)| (name, captures.get(index).map(Match::from)));
+        Match::groups(groups)
+    }
+
+    /// Same as `captures`, for the capture groups found by an engine of
+    /// `regex-automata` in `text`.
+    pub fn automata_captures(
+        text: &'t str,
+        captures: &regex_automata::util::captures::Captures,
+    ) -> Result<JsValue, serde_wasm_bindgen::Error> {
+        let names = captures
+            .group_info()
+            .pattern_names(regex_automata::PatternID::ZERO);
+        let groups = names.enumerate().map(|(index, name)| {
+            let m = captures
+                .get_group(index)
+                .map(|span| Match::new(text, span.start, span.end));
+            (name, m)
+        });
+        Match::groups(groups)
+    }
+
+    fn groups<'n, I>(groups: I) -> Result<JsValue, serde_wasm_bindgen::Error>
+    where
+        I: Iterator<Item = (Option<&'n str>, Option<Match<'t>>)>,
+    {
         let matches = js_sys::Array::new();
         let names = js_sys::Object::new();
-        for (index, name) in captures_names.enumerate() {
-            if let Some(m) = captures.get(index) {
-                let v = JsValue::try_from(Match::from(m))?;
+        for (name, m) in groups {
+            if let Some(m) = m {
+                let v = JsValue::try_from(m)?;
                 matches.push(&v);
-            }
-
-            if let Some(n) = name {
-                if let Some(m) = captures.name(n) {
-                    let v = JsValue::try_from(Match::from(m))?;
+                if let Some(n) = name {
                     js_sys::Reflect::set(&names, &JsValue::from(n), &v)?;
                 }
             }